@@ -0,0 +1,230 @@
+use std::ffi::OsStr;
+use std::time::Duration;
+
+/// Whether the terminal has a light or dark background.
+///
+/// Use [`ColorNope::background_for`](crate::ColorNope::background_for) to
+/// detect it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Background {
+    /// A light background, so dark foreground colors should be used.
+    Light,
+    /// A dark background, so light foreground colors should be used.
+    Dark,
+    /// The background could not be detected.
+    Unknown,
+}
+
+impl Background {
+    /// Parse a `COLORFGBG` value, such as `15;0`, as set by rxvt, Konsole and
+    /// others.
+    ///
+    /// The last field is the background's ANSI color index, where 0-6 and 8
+    /// are dark, and 7 and 9-15 are light.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::ffi::OsStr;
+    /// use color_nope::Background;
+    ///
+    /// assert_eq!(Background::from_colorfgbg(OsStr::new("15;0")), Background::Dark);
+    /// assert_eq!(Background::from_colorfgbg(OsStr::new("0;default;15")), Background::Light);
+    /// assert_eq!(Background::from_colorfgbg(OsStr::new("default;default")), Background::Unknown);
+    /// ```
+    pub fn from_colorfgbg(value: &OsStr) -> Background {
+        let bg = value
+            .to_str()
+            .and_then(|value| value.rsplit(';').next())
+            .and_then(|bg| bg.parse::<u8>().ok());
+        match bg {
+            Some(0..=6 | 8) => Background::Dark,
+            Some(7 | 9..=15) => Background::Light,
+            _ => Background::Unknown,
+        }
+    }
+
+    /// Classify an RGB color, with each component between 0.0 and 1.0.
+    #[cfg(unix)]
+    fn from_rgb(r: f64, g: f64, b: f64) -> Background {
+        let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        if luminance > 0.5 {
+            Background::Light
+        } else {
+            Background::Dark
+        }
+    }
+
+    /// Parse a response to an OSC 11 query, e.g. `ESC ] 11 ; rgb:RRRR/GGGG/BBBB BEL`.
+    #[cfg(unix)]
+    pub(crate) fn from_osc11_response(response: &[u8]) -> Background {
+        let response = String::from_utf8_lossy(response);
+        let rgb = response
+            .split("]11;rgb:")
+            .nth(1)
+            .map(|rgb| rgb.trim_end_matches(['\x07', '\x1b', '\\']));
+        let mut components = match rgb {
+            Some(rgb) => rgb.split('/').map(|hex| {
+                let max = 16f64.powi(hex.len() as i32) - 1.0;
+                u16::from_str_radix(hex, 16)
+                    .ok()
+                    .map(|v| f64::from(v) / max)
+            }),
+            None => return Background::Unknown,
+        };
+        match (
+            components.next().flatten(),
+            components.next().flatten(),
+            components.next().flatten(),
+        ) {
+            (Some(r), Some(g), Some(b)) => Background::from_rgb(r, g, b),
+            _ => Background::Unknown,
+        }
+    }
+}
+
+/// Ask the controlling terminal for its background color using OSC 11.
+///
+/// Returns [`Background::Unknown`] without touching the terminal unless the
+/// process is in the terminal's foreground process group, as changing its
+/// settings from the background would stop the process with `SIGTTOU`.
+#[cfg(unix)]
+pub(crate) fn query_osc11(timeout: Duration) -> Background {
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    let mut tty = match OpenOptions::new().read(true).write(true).open("/dev/tty") {
+        Ok(tty) => tty,
+        Err(_) => return Background::Unknown,
+    };
+    let fd = tty.as_raw_fd();
+
+    // SAFETY: fd is an open file descriptor, owned by tty.
+    if unsafe { libc::tcgetpgrp(fd) != libc::getpgrp() } {
+        return Background::Unknown;
+    }
+
+    // Disable line buffering and echo, so the response can be read without
+    // the user pressing enter, and isn't printed.
+    // SAFETY: termios is plain data, and is initialised by tcgetattr.
+    let mut original: libc::termios = unsafe { std::mem::zeroed() };
+    // SAFETY: fd is an open file descriptor and original is a valid termios.
+    if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+        return Background::Unknown;
+    }
+    let mut raw = original;
+    raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+    // Make reads return immediately, so they can't block past the deadline.
+    raw.c_cc[libc::VMIN] = 0;
+    raw.c_cc[libc::VTIME] = 0;
+    // SAFETY: fd is an open file descriptor and raw is a valid termios.
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+        return Background::Unknown;
+    }
+
+    let mut response = Vec::new();
+    if tty
+        .write_all(b"\x1b]11;?\x1b\\")
+        .and_then(|_| tty.flush())
+        .is_ok()
+    {
+        let deadline = Instant::now() + timeout;
+        let mut buf = [0; 64];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let mut pollfd = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let millis = remaining.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+            // SAFETY: pollfd is a single valid pollfd, matching nfds of 1.
+            if remaining.is_zero() || unsafe { libc::poll(&mut pollfd, 1, millis) } <= 0 {
+                break;
+            }
+            match tty.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => response.extend_from_slice(&buf[..n]),
+            }
+            if response.ends_with(b"\x07") || response.ends_with(b"\x1b\\") {
+                break;
+            }
+        }
+    }
+
+    // SAFETY: fd is still open, and original was filled in by tcgetattr.
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+    Background::from_osc11_response(&response)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn query_osc11(_timeout: Duration) -> Background {
+    Background::Unknown
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::Background;
+
+    #[test]
+    fn osc11_bel_terminated() {
+        assert_eq!(
+            Background::from_osc11_response(b"\x1b]11;rgb:0000/0000/0000\x07"),
+            Background::Dark
+        );
+    }
+
+    #[test]
+    fn osc11_st_terminated() {
+        assert_eq!(
+            Background::from_osc11_response(b"\x1b]11;rgb:ffff/ffff/ffff\x1b\\"),
+            Background::Light
+        );
+    }
+
+    #[test]
+    fn osc11_two_digit_components() {
+        assert_eq!(
+            Background::from_osc11_response(b"\x1b]11;rgb:fd/f6/e3\x07"),
+            Background::Light
+        );
+        assert_eq!(
+            Background::from_osc11_response(b"\x1b]11;rgb:28/2c/34\x1b\\"),
+            Background::Dark
+        );
+    }
+
+    #[test]
+    fn osc11_four_digit_components() {
+        assert_eq!(
+            Background::from_osc11_response(b"\x1b]11;rgb:fdfd/f6f6/e3e3\x07"),
+            Background::Light
+        );
+        assert_eq!(
+            Background::from_osc11_response(b"\x1b]11;rgb:2828/2c2c/3434\x07"),
+            Background::Dark
+        );
+    }
+
+    #[test]
+    fn osc11_malformed() {
+        for response in [
+            &b""[..],
+            b"\x1b]11;?\x07",
+            b"\x1b]11;rgb:ffff/ffff\x07",
+            b"\x1b]11;rgb:zzzz/ffff/ffff\x07",
+            b"\x1b]11;rgb:fffff/ffff/ffff\x07",
+            b"\x1b]11;rgb://\x07",
+            b"\x1b]10;rgb:ffff/ffff/ffff\x07",
+        ] {
+            assert_eq!(
+                Background::from_osc11_response(response),
+                Background::Unknown,
+                "{:?}",
+                String::from_utf8_lossy(response)
+            );
+        }
+    }
+}
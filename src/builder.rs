@@ -0,0 +1,233 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{
+    CiProvider, ColorChoice, ColorNope, Force, InvalidEnvVar, Stream, TerminalProbe, Terminfo,
+};
+
+/// Builds a [`ColorNope`] from named inputs.
+///
+/// Created using [`ColorNope::builder`]. Every input is optional, and inputs
+/// which are not set are treated as unset environmental variables.
+///
+/// # Example
+///
+/// ```rust
+/// use color_nope::{ColorChoice, ColorNope, FakeTerminal, Stream};
+///
+/// let color = ColorNope::builder()
+///     .term(std::env::var_os("TERM"))
+///     .no_color(std::env::var_os("NO_COLOR"))
+///     .clicolor(Some("0".into()))
+///     .color_choice(ColorChoice::Auto)
+///     .probe(FakeTerminal::all())
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(color.enable_color_for(Stream::Stdout), false);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ColorNopeBuilder {
+    term_env: Option<OsString>,
+    no_color_env: Option<OsString>,
+    force_color: Option<Force>,
+    color_choice: Option<ColorChoice>,
+    stream_choices: Vec<(Stream, ColorChoice)>,
+    colorterm_env: Option<OsString>,
+    term_program_env: Option<OsString>,
+    clicolor_env: Option<OsString>,
+    clicolor_force_env: Option<OsString>,
+    force_color_env: Option<OsString>,
+    terminfo: Option<Terminfo>,
+    ci: Option<CiProvider>,
+    colorfgbg_env: Option<OsString>,
+    probe: Option<Arc<dyn TerminalProbe>>,
+}
+
+impl ColorNopeBuilder {
+    /// The value of the `TERM` environmental variable.
+    pub fn term(mut self, term_env: Option<OsString>) -> Self {
+        self.term_env = term_env;
+        self
+    }
+
+    /// The value of the `NO_COLOR` environmental variable.
+    pub fn no_color(mut self, no_color_env: Option<OsString>) -> Self {
+        self.no_color_env = no_color_env;
+        self
+    }
+
+    /// Override other settings to force colors on or off.
+    pub fn force(mut self, force_color: Option<Force>) -> Self {
+        self.force_color = force_color;
+        self
+    }
+
+    /// A [`ColorChoice`], e.g. from a `--color` argument.
+    ///
+    /// This is an alternative to [`force`](ColorNopeBuilder::force). Setting
+    /// both to different values is an error.
+    pub fn color_choice(mut self, color_choice: ColorChoice) -> Self {
+        self.color_choice = Some(color_choice);
+        self
+    }
+
+    /// A [`ColorChoice`] for one stream.
+    ///
+    /// See [`ColorNope::with_stream_choice`].
+    pub fn stream_choice(mut self, stream: Stream, choice: ColorChoice) -> Self {
+        self.stream_choices.push((stream, choice));
+        self
+    }
+
+    /// The value of the `COLORTERM` environmental variable.
+    pub fn colorterm(mut self, colorterm_env: Option<OsString>) -> Self {
+        self.colorterm_env = colorterm_env;
+        self
+    }
+
+    /// The value of the `TERM_PROGRAM` environmental variable.
+    pub fn term_program(mut self, term_program_env: Option<OsString>) -> Self {
+        self.term_program_env = term_program_env;
+        self
+    }
+
+    /// The value of the `CLICOLOR` environmental variable.
+    pub fn clicolor(mut self, clicolor_env: Option<OsString>) -> Self {
+        self.clicolor_env = clicolor_env;
+        self
+    }
+
+    /// The value of the `CLICOLOR_FORCE` environmental variable.
+    pub fn clicolor_force(mut self, clicolor_force_env: Option<OsString>) -> Self {
+        self.clicolor_force_env = clicolor_force_env;
+        self
+    }
+
+    /// The value of the `FORCE_COLOR` environmental variable.
+    ///
+    /// Validated by [`build`](ColorNopeBuilder::build).
+    pub fn force_color(mut self, force_color_env: Option<OsString>) -> Self {
+        self.force_color_env = force_color_env;
+        self
+    }
+
+    /// The terminal's [`Terminfo`] entry.
+    ///
+    /// See [`ColorNope::with_terminfo`].
+    pub fn terminfo(mut self, terminfo: Option<&Terminfo>) -> Self {
+        self.terminfo = terminfo.cloned();
+        self
+    }
+
+    /// The [`CiProvider`] the process is running in.
+    ///
+    /// See [`ColorNope::with_ci`].
+    pub fn ci(mut self, ci: Option<CiProvider>) -> Self {
+        self.ci = ci;
+        self
+    }
+
+    /// The value of the `COLORFGBG` environmental variable.
+    pub fn colorfgbg(mut self, colorfgbg_env: Option<OsString>) -> Self {
+        self.colorfgbg_env = colorfgbg_env;
+        self
+    }
+
+    /// The [`TerminalProbe`] used to check whether a stream is a terminal.
+    pub fn probe(mut self, probe: impl TerminalProbe + 'static) -> Self {
+        self.probe = Some(Arc::new(probe));
+        self
+    }
+
+    /// Validate the inputs and create the [`ColorNope`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::{BuildError, ColorChoice, ColorNope, Force};
+    ///
+    /// let err = ColorNope::builder()
+    ///     .force(Some(Force::On))
+    ///     .color_choice(ColorChoice::Never)
+    ///     .build()
+    ///     .unwrap_err();
+    /// assert!(matches!(err, BuildError::ConflictingForce { .. }));
+    ///
+    /// let err = ColorNope::builder()
+    ///     .force_color(Some("lots".into()))
+    ///     .build()
+    ///     .unwrap_err();
+    /// assert!(matches!(err, BuildError::InvalidEnvVar(_)));
+    /// ```
+    pub fn build(self) -> Result<ColorNope, BuildError> {
+        let force_color = match (self.force_color, self.color_choice) {
+            (Some(force), Some(choice)) if choice.force() != Some(force) => {
+                return Err(BuildError::ConflictingForce { force, choice })
+            }
+            (None, Some(choice)) => choice.force(),
+            (force, _) => force,
+        };
+
+        let mut color = ColorNope::new(self.term_env, self.no_color_env, force_color)
+            .with_colorterm(self.colorterm_env)
+            .with_term_program(self.term_program_env)
+            .with_clicolor(self.clicolor_env)
+            .with_clicolor_force(self.clicolor_force_env)
+            .with_terminfo(self.terminfo.as_ref())
+            .with_ci(self.ci)
+            .with_colorfgbg(self.colorfgbg_env)
+            .with_force_color_env(self.force_color_env)?;
+        for (stream, choice) in self.stream_choices {
+            color = color.with_stream_choice(stream, choice);
+        }
+        if let Some(probe) = self.probe {
+            color.probe = probe;
+        }
+        Ok(color)
+    }
+}
+
+/// An error returned by [`ColorNopeBuilder::build`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// An environmental variable had an invalid value.
+    InvalidEnvVar(InvalidEnvVar),
+    /// [`force`](ColorNopeBuilder::force) and
+    /// [`color_choice`](ColorNopeBuilder::color_choice) disagree.
+    ConflictingForce {
+        /// The value given to `force`.
+        force: Force,
+        /// The value given to `color_choice`.
+        choice: ColorChoice,
+    },
+}
+
+impl From<InvalidEnvVar> for BuildError {
+    fn from(err: InvalidEnvVar) -> Self {
+        BuildError::InvalidEnvVar(err)
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::InvalidEnvVar(err) => err.fmt(f),
+            BuildError::ConflictingForce { force, choice } => write!(
+                f,
+                "conflicting color settings: force is {force:?} but color choice is {choice}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::InvalidEnvVar(err) => Some(err),
+            BuildError::ConflictingForce { .. } => None,
+        }
+    }
+}
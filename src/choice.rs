@@ -0,0 +1,242 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Force, Stream};
+
+/// The value of a standard `--color=<WHEN>` command line argument.
+///
+/// Parses `auto`, `always` and `never`, along with the common aliases `tty`
+/// (`auto`), `yes` and `true` (`always`), and `no` and `false` (`never`).
+///
+/// # Example
+///
+/// ```rust
+/// use color_nope::{ColorChoice, ColorNope, Force};
+///
+/// let choice: ColorChoice = "never".parse().unwrap();
+/// assert_eq!(choice.force(), Some(Force::Off));
+///
+/// let color = ColorNope::new(
+///     std::env::var_os("TERM"),
+///     std::env::var_os("NO_COLOR"),
+///     choice.into(),
+/// );
+///
+/// let err = "sometimes".parse::<ColorChoice>().unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "invalid color choice \"sometimes\", expected one of: auto, always, never, tty, yes, no, true, false"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorChoice {
+    /// Decide based on the environment and the target stream.
+    #[default]
+    #[cfg_attr(feature = "clap", value(alias = "tty"))]
+    Auto,
+    /// Always enable color.
+    #[cfg_attr(feature = "clap", value(aliases = ["yes", "true"]))]
+    Always,
+    /// Never enable color.
+    #[cfg_attr(feature = "clap", value(aliases = ["no", "false"]))]
+    Never,
+}
+
+impl ColorChoice {
+    /// The [`Force`] override for this choice, if any.
+    pub fn force(&self) -> Option<Force> {
+        match self {
+            ColorChoice::Auto => None,
+            ColorChoice::Always => Some(Force::On),
+            ColorChoice::Never => Some(Force::Off),
+        }
+    }
+}
+
+impl From<ColorChoice> for Option<Force> {
+    fn from(choice: ColorChoice) -> Self {
+        choice.force()
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = ParseColorChoiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" | "tty" => Ok(ColorChoice::Auto),
+            "always" | "yes" | "true" => Ok(ColorChoice::Always),
+            "never" | "no" | "false" => Ok(ColorChoice::Never),
+            _ => Err(ParseColorChoiceError {
+                value: s.to_owned(),
+                kind: ErrorKind::Choice,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for ColorChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        })
+    }
+}
+
+/// A [`ColorChoice`] for each [`Stream`], parsed from a value such as
+/// `stderr:always,stdout:auto`.
+///
+/// The value is a comma-separated list. Each item is either a
+/// [`ColorChoice`], which applies to every stream, or `<stream>:<choice>`,
+/// which applies to one stream. Later items take precedence, so a plain
+/// choice replaces any per-stream choices before it. A plain choice such as
+/// `never` is also valid.
+///
+/// # Example
+///
+/// ```rust
+/// use color_nope::{ColorChoice, ColorChoices, ColorNope, Stream};
+///
+/// let choices: ColorChoices = "never,stderr:always".parse().unwrap();
+/// assert_eq!(choices.for_stream(Stream::Stdout), ColorChoice::Never);
+/// assert_eq!(choices.for_stream(Stream::Stderr), ColorChoice::Always);
+/// assert_eq!(choices.to_string(), "never,stderr:always");
+///
+/// let color = ColorNope::from_env().with_color_choices(choices);
+/// assert_eq!(color.enable_color_for(Stream::Stderr), true);
+///
+/// let choices: ColorChoices = "stdout:always,never".parse().unwrap();
+/// assert_eq!(choices.for_stream(Stream::Stdout), ColorChoice::Never);
+/// assert_eq!(choices.to_string(), "never");
+///
+/// let err = "stdin:always".parse::<ColorChoices>().unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "invalid stream \"stdin\", expected stdout or stderr"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct ColorChoices {
+    default: ColorChoice,
+    stdout: Option<ColorChoice>,
+    stderr: Option<ColorChoice>,
+}
+
+impl ColorChoices {
+    /// Use the same choice for every stream.
+    pub fn new(default: ColorChoice) -> ColorChoices {
+        ColorChoices {
+            default,
+            stdout: None,
+            stderr: None,
+        }
+    }
+
+    /// Use a different choice for one stream.
+    pub fn with_stream(mut self, stream: Stream, choice: ColorChoice) -> ColorChoices {
+        match stream {
+            Stream::Stdout => self.stdout = Some(choice),
+            Stream::Stderr => self.stderr = Some(choice),
+        }
+        self
+    }
+
+    /// The choice for streams without their own choice.
+    pub fn default_choice(&self) -> ColorChoice {
+        self.default
+    }
+
+    /// The choice given for a specific stream, if any.
+    pub fn stream_choice(&self, stream: Stream) -> Option<ColorChoice> {
+        match stream {
+            Stream::Stdout => self.stdout,
+            Stream::Stderr => self.stderr,
+        }
+    }
+
+    /// The choice which applies to a stream.
+    pub fn for_stream(&self, stream: Stream) -> ColorChoice {
+        self.stream_choice(stream).unwrap_or(self.default)
+    }
+}
+
+impl From<ColorChoice> for ColorChoices {
+    fn from(choice: ColorChoice) -> Self {
+        ColorChoices::new(choice)
+    }
+}
+
+impl FromStr for ColorChoices {
+    type Err = ParseColorChoiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .try_fold(ColorChoices::default(), |choices, item| {
+                match item.split_once(':') {
+                    None => Ok(ColorChoices::new(item.parse()?)),
+                    Some((stream, choice)) => {
+                        let stream = match stream {
+                            "stdout" => Stream::Stdout,
+                            "stderr" => Stream::Stderr,
+                            _ => {
+                                return Err(ParseColorChoiceError {
+                                    value: stream.to_owned(),
+                                    kind: ErrorKind::Stream,
+                                })
+                            }
+                        };
+                        Ok(choices.with_stream(stream, choice.parse()?))
+                    }
+                }
+            })
+    }
+}
+
+impl fmt::Display for ColorChoices {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.default)?;
+        if let Some(choice) = self.stdout {
+            write!(f, ",stdout:{choice}")?;
+        }
+        if let Some(choice) = self.stderr {
+            write!(f, ",stderr:{choice}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An invalid [`ColorChoice`] or [`ColorChoices`] value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseColorChoiceError {
+    value: String,
+    kind: ErrorKind,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ErrorKind {
+    Choice,
+    Stream,
+}
+
+impl fmt::Display for ParseColorChoiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Choice => write!(
+                f,
+                "invalid color choice {:?}, expected one of: auto, always, never, tty, yes, no, true, false",
+                self.value
+            ),
+            ErrorKind::Stream => write!(
+                f,
+                "invalid stream {:?}, expected stdout or stderr",
+                self.value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseColorChoiceError {}
@@ -0,0 +1,199 @@
+use std::fmt;
+
+use crate::{ColorLevel, EnvSource, ProcessEnv};
+
+/// A continuous integration provider.
+///
+/// CI jobs rarely write to a terminal, but many providers render ANSI colors
+/// in their log viewers. Use
+/// [`ColorNope::with_ci`](crate::ColorNope::with_ci) to enable color for
+/// these providers even without a terminal.
+///
+/// GitHub Actions renders 24-bit color. Every other named provider is assumed
+/// to render the 16 basic colors, and [`CiProvider::Other`] is assumed not to
+/// render colors at all. See [`color_level`](CiProvider::color_level).
+///
+/// # Example
+///
+/// ```rust
+/// use std::ffi::OsString;
+/// use color_nope::{CiProvider, ColorLevel, ColorNope, FakeTerminal, Reason, Stream};
+///
+/// let env = |key: &str| match key {
+///     "CI" | "GITLAB_CI" => Some(OsString::from("true")),
+///     _ => None,
+/// };
+/// let ci = CiProvider::detect(&env);
+/// assert_eq!(ci, Some(CiProvider::GitLab));
+///
+/// let decision = ColorNope::new(None, None, None)
+///     .with_ci(ci)
+///     .with_probe(FakeTerminal::none())
+///     .decide(Stream::Stdout);
+/// assert_eq!(decision.reason(), Reason::Ci(CiProvider::GitLab));
+/// assert_eq!(decision.level(), ColorLevel::Basic16);
+///
+/// let decision = ColorNope::new(None, None, None)
+///     .with_ci(Some(CiProvider::Jenkins))
+///     .with_probe(FakeTerminal::none())
+///     .decide(Stream::Stdout);
+/// assert_eq!(decision.enabled(), true);
+/// assert_eq!(decision.reason(), Reason::Ci(CiProvider::Jenkins));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum CiProvider {
+    /// GitHub Actions (`GITHUB_ACTIONS`).
+    GitHubActions,
+    /// GitLab CI (`GITLAB_CI`).
+    GitLab,
+    /// Buildkite (`BUILDKITE`).
+    Buildkite,
+    /// Azure Pipelines (`TF_BUILD`).
+    AzurePipelines,
+    /// Jenkins (`JENKINS_URL`). Colors are shown when the AnsiColor plugin
+    /// is installed, as it is on most instances.
+    Jenkins,
+    /// CircleCI (`CIRCLECI`).
+    CircleCi,
+    /// Travis CI (`TRAVIS`).
+    TravisCi,
+    /// AppVeyor (`APPVEYOR`).
+    AppVeyor,
+    /// TeamCity (`TEAMCITY_VERSION`).
+    TeamCity,
+    /// Drone (`DRONE`).
+    Drone,
+    /// An unknown provider, detected using the generic `CI` variable.
+    Other,
+}
+
+/// The variable identifying each provider, and the [`ColorLevel`] its log
+/// viewer supports, in detection order.
+const PROVIDERS: &[(&str, CiProvider, ColorLevel)] = &[
+    (
+        "GITHUB_ACTIONS",
+        CiProvider::GitHubActions,
+        ColorLevel::TrueColor,
+    ),
+    ("GITLAB_CI", CiProvider::GitLab, ColorLevel::Basic16),
+    ("BUILDKITE", CiProvider::Buildkite, ColorLevel::Basic16),
+    ("TF_BUILD", CiProvider::AzurePipelines, ColorLevel::Basic16),
+    ("JENKINS_URL", CiProvider::Jenkins, ColorLevel::Basic16),
+    ("CIRCLECI", CiProvider::CircleCi, ColorLevel::Basic16),
+    ("TRAVIS", CiProvider::TravisCi, ColorLevel::Basic16),
+    ("APPVEYOR", CiProvider::AppVeyor, ColorLevel::Basic16),
+    (
+        "TEAMCITY_VERSION",
+        CiProvider::TeamCity,
+        ColorLevel::Basic16,
+    ),
+    ("DRONE", CiProvider::Drone, ColorLevel::Basic16),
+    ("CI", CiProvider::Other, ColorLevel::None),
+];
+
+impl CiProvider {
+    /// Detect the CI provider using [`ProcessEnv`].
+    pub fn from_env() -> Option<CiProvider> {
+        CiProvider::detect(&ProcessEnv)
+    }
+
+    /// Detect the CI provider from the variables in `source`.
+    ///
+    /// Returns `None` when not running in CI. Values of `false` and empty
+    /// values are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::ffi::OsString;
+    /// use color_nope::CiProvider;
+    ///
+    /// let env = |key: &str| match key {
+    ///     "CI" | "BUILDKITE" => Some(OsString::from("true")),
+    ///     _ => None,
+    /// };
+    /// assert_eq!(CiProvider::detect(&env), Some(CiProvider::Buildkite));
+    ///
+    /// let env = |key: &str| (key == "CI").then(|| OsString::from("false"));
+    /// assert_eq!(CiProvider::detect(&env), None);
+    /// ```
+    pub fn detect(source: &impl EnvSource) -> Option<CiProvider> {
+        PROVIDERS.iter().find_map(|(var, provider, _)| {
+            source
+                .var_os(var)
+                .filter(|value| !value.is_empty() && value != "false")
+                .map(|_| *provider)
+        })
+    }
+
+    /// The [`ColorLevel`] rendered by this provider's log viewer.
+    ///
+    /// [`ColorLevel::None`] means escape sequences are not rendered.
+    pub fn color_level(&self) -> ColorLevel {
+        PROVIDERS
+            .iter()
+            .find(|(_, provider, _)| provider == self)
+            .map_or(ColorLevel::None, |(_, _, level)| *level)
+    }
+
+    /// The name of the provider.
+    pub fn name(&self) -> &'static str {
+        use CiProvider::*;
+        match self {
+            GitHubActions => "GitHub Actions",
+            GitLab => "GitLab CI",
+            Buildkite => "Buildkite",
+            AzurePipelines => "Azure Pipelines",
+            Jenkins => "Jenkins",
+            CircleCi => "CircleCI",
+            TravisCi => "Travis CI",
+            AppVeyor => "AppVeyor",
+            TeamCity => "TeamCity",
+            Drone => "Drone",
+            Other => "CI",
+        }
+    }
+}
+
+impl fmt::Display for CiProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use crate::{CiProvider, ColorLevel};
+
+    #[test]
+    fn providers() {
+        for (var, provider, level) in [
+            (
+                "GITHUB_ACTIONS",
+                CiProvider::GitHubActions,
+                ColorLevel::TrueColor,
+            ),
+            ("GITLAB_CI", CiProvider::GitLab, ColorLevel::Basic16),
+            ("BUILDKITE", CiProvider::Buildkite, ColorLevel::Basic16),
+            ("JENKINS_URL", CiProvider::Jenkins, ColorLevel::Basic16),
+            ("TF_BUILD", CiProvider::AzurePipelines, ColorLevel::Basic16),
+            ("CI", CiProvider::Other, ColorLevel::None),
+        ] {
+            let env = |key: &str| (key == var || key == "CI").then(|| OsString::from("true"));
+            assert_eq!(CiProvider::detect(&env), Some(provider), "{var}");
+            assert_eq!(provider.color_level(), level, "{var}");
+        }
+    }
+
+    #[test]
+    fn not_in_ci() {
+        let env = |key: &str| (key == "CI").then(|| OsString::from("false"));
+        assert_eq!(CiProvider::detect(&env), None);
+        let env = |key: &str| (key == "GITLAB_CI").then(OsString::new);
+        assert_eq!(CiProvider::detect(&env), None);
+    }
+}
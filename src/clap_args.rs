@@ -0,0 +1,111 @@
+use std::str::FromStr;
+
+use crate::{ColorChoice, ColorChoices, ColorNope, Force, InvalidEnvVar};
+
+/// Ready-made [`clap`] arguments for `--color <WHEN>` and `--no-color`.
+///
+/// `--color` takes [`ColorChoices`], so per-stream values such as
+/// `--color=stderr:always,stdout:auto` are accepted as well as a plain
+/// choice.
+///
+/// Requires the `clap` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use clap::Parser;
+/// use color_nope::{ColorArgs, ColorChoice, ColorNope, Force, Stream};
+///
+/// #[derive(Parser)]
+/// struct Cli {
+///     #[command(flatten)]
+///     color: ColorArgs,
+/// }
+///
+/// let cli = Cli::parse_from(["app", "--color", "always"]);
+/// assert_eq!(cli.color.choice(), ColorChoice::Always);
+///
+/// let cli = Cli::parse_from(["app", "--color=stderr:always,stdout:auto"]);
+/// assert_eq!(cli.color.choices().for_stream(Stream::Stderr), ColorChoice::Always);
+/// assert_eq!(cli.color.choices().for_stream(Stream::Stdout), ColorChoice::Auto);
+///
+/// let cli = Cli::parse_from(["app", "--no-color"]);
+/// assert_eq!(cli.color.force(), Some(Force::Off));
+///
+/// let color = ColorNope::try_from(cli.color).unwrap();
+/// assert_eq!(color.enable_color_for(Stream::Stdout), false);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::Args)]
+// Stop this documentation becoming the about text of the parent command.
+#[command(about = None, long_about = None)]
+pub struct ColorArgs {
+    /// When to use color: auto, always or never, optionally per stream
+    /// (e.g. `stderr:always,stdout:auto`)
+    #[arg(
+        long,
+        value_name = "WHEN",
+        value_parser = ColorChoices::from_str,
+        default_value_t = ColorChoices::default()
+    )]
+    pub color: ColorChoices,
+
+    /// Disable color, the same as `--color never`
+    #[arg(long, conflicts_with = "color")]
+    pub no_color: bool,
+}
+
+impl ColorArgs {
+    /// The [`ColorChoices`] given on the command line.
+    pub fn choices(&self) -> ColorChoices {
+        if self.no_color {
+            ColorChoices::new(ColorChoice::Never)
+        } else {
+            self.color
+        }
+    }
+
+    /// The [`ColorChoice`] given on the command line for streams without
+    /// their own choice.
+    pub fn choice(&self) -> ColorChoice {
+        self.choices().default_choice()
+    }
+
+    /// The [`Force`] override given on the command line for streams without
+    /// their own choice, if any.
+    pub fn force(&self) -> Option<Force> {
+        self.choice().force()
+    }
+}
+
+/// Uses [`ColorNope::try_from_env`], overridden by the command line
+/// arguments.
+///
+/// Returns an error if `FORCE_COLOR` has an invalid value, even when the
+/// command line arguments would override it.
+impl TryFrom<ColorArgs> for ColorNope {
+    type Error = InvalidEnvVar;
+
+    fn try_from(args: ColorArgs) -> Result<Self, Self::Error> {
+        Ok(ColorNope::try_from_env()?.with_color_choices(args.choices()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, Parser};
+
+    use super::ColorArgs;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        color: ColorArgs,
+    }
+
+    #[test]
+    fn no_about_text_for_parent_command() {
+        let command = Cli::command();
+        assert_eq!(command.get_about(), None);
+        assert_eq!(command.get_long_about(), None);
+    }
+}
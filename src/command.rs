@@ -0,0 +1,95 @@
+use std::ffi::OsStr;
+use std::process::Command;
+
+use crate::{ColorLevel, Decision};
+
+impl Decision {
+    /// Configure a child process to make the same decision.
+    ///
+    /// Children usually write to a pipe which the parent forwards, so they
+    /// would otherwise turn color off. This sets or removes the following
+    /// environmental variables:
+    ///
+    /// | Variable           | Color enabled             | Color disabled   |
+    /// |--------------------|---------------------------|------------------|
+    /// | `NO_COLOR`         | removed                   | `1`              |
+    /// | `CLICOLOR`         | `1`                       | `0`              |
+    /// | `CLICOLOR_FORCE`   | `1`                       | removed          |
+    /// | `FORCE_COLOR`      | `1`, `2` or `3` by level  | `0`              |
+    /// | `COLORTERM`        | `truecolor` for 24-bit    | unchanged        |
+    /// | `CARGO_TERM_COLOR` | `always`                  | `never`          |
+    /// | `GIT_CONFIG_*`     | `color.ui=always`         | `color.ui=never` |
+    ///
+    /// `git` ignores the other variables, so `color.ui` is set by appending
+    /// an entry after any existing `GIT_CONFIG_COUNT` entries. An invalid
+    /// `GIT_CONFIG_COUNT` is left alone.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::process::Command;
+    /// use color_nope::{ColorNope, Force, Stream};
+    ///
+    /// let mut cmd = Command::new("cargo");
+    /// ColorNope::new(None, None, Some(Force::Off))
+    ///     .decide(Stream::Stdout)
+    ///     .configure_command(&mut cmd)
+    ///     .arg("build");
+    ///
+    /// let no_color = cmd.get_envs().find(|(key, _)| *key == "NO_COLOR");
+    /// assert_eq!(no_color, Some(("NO_COLOR".as_ref(), Some("1".as_ref()))));
+    ///
+    /// let mut cmd = Command::new("git");
+    /// cmd.env("GIT_CONFIG_COUNT", "1");
+    /// ColorNope::new(None, None, Some(Force::On))
+    ///     .decide(Stream::Stdout)
+    ///     .configure_command(&mut cmd);
+    ///
+    /// let env = |name: &str| cmd.get_envs().find(|(key, _)| *key == name).and_then(|(_, v)| v);
+    /// assert_eq!(env("GIT_CONFIG_COUNT"), Some("2".as_ref()));
+    /// assert_eq!(env("GIT_CONFIG_KEY_1"), Some("color.ui".as_ref()));
+    /// assert_eq!(env("GIT_CONFIG_VALUE_1"), Some("always".as_ref()));
+    /// ```
+    pub fn configure_command<'a>(&self, cmd: &'a mut Command) -> &'a mut Command {
+        configure_git(cmd, if self.enabled() { "always" } else { "never" });
+        match self.level() {
+            ColorLevel::None => cmd
+                .env("NO_COLOR", "1")
+                .env("CLICOLOR", "0")
+                .env_remove("CLICOLOR_FORCE")
+                .env("FORCE_COLOR", "0")
+                .env("CARGO_TERM_COLOR", "never"),
+            level => {
+                cmd.env_remove("NO_COLOR")
+                    .env("CLICOLOR", "1")
+                    .env("CLICOLOR_FORCE", "1")
+                    .env("CARGO_TERM_COLOR", "always");
+                match level {
+                    ColorLevel::TrueColor => {
+                        cmd.env("FORCE_COLOR", "3").env("COLORTERM", "truecolor")
+                    }
+                    ColorLevel::Ansi256 => cmd.env("FORCE_COLOR", "2"),
+                    _ => cmd.env("FORCE_COLOR", "1"),
+                }
+            }
+        }
+    }
+}
+
+/// Set git's `color.ui` using `GIT_CONFIG_COUNT`, after any existing entries.
+fn configure_git(cmd: &mut Command, color_ui: &str) {
+    let count = match cmd.get_envs().find(|(key, _)| *key == "GIT_CONFIG_COUNT") {
+        Some((_, value)) => value.map(OsStr::to_owned),
+        None => std::env::var_os("GIT_CONFIG_COUNT"),
+    };
+    let count = match count {
+        None => 0,
+        Some(count) => match count.to_str().map(str::parse::<usize>) {
+            Some(Ok(count)) => count,
+            _ => return,
+        },
+    };
+    cmd.env(format!("GIT_CONFIG_KEY_{count}"), "color.ui")
+        .env(format!("GIT_CONFIG_VALUE_{count}"), color_ui)
+        .env("GIT_CONFIG_COUNT", (count + 1).to_string());
+}
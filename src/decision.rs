@@ -0,0 +1,280 @@
+use std::fmt;
+
+use crate::{CiProvider, ColorLevel, ForceColor};
+
+/// The outcome of [`ColorNope::decide`](crate::ColorNope::decide), along with
+/// the reason for it.
+///
+/// The [`Display`](fmt::Display) implementation gives a human-readable
+/// explanation, suitable for printing from something like a `--debug-color`
+/// flag.
+///
+/// # Example
+///
+/// ```rust
+/// use color_nope::{ColorNope, FakeTerminal, Reason, Stream};
+///
+/// let decision = ColorNope::new(Some("xterm".into()), Some("1".into()), None)
+///     .with_probe(FakeTerminal::all())
+///     .decide(Stream::Stdout);
+///
+/// assert_eq!(decision.enabled(), false);
+/// assert_eq!(decision.reason(), Reason::NoColor);
+/// assert_eq!(decision.to_string(), "color disabled: NO_COLOR is set");
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Decision {
+    level: ColorLevel,
+    reason: Reason,
+    styles: bool,
+}
+
+impl Decision {
+    pub(crate) fn new(level: ColorLevel, reason: Reason) -> Decision {
+        Decision {
+            level,
+            reason,
+            styles: false,
+        }
+    }
+
+    /// Allow non-color attributes even if color is disabled.
+    pub(crate) fn with_styles(mut self, styles: bool) -> Decision {
+        self.styles = styles;
+        self
+    }
+
+    /// Should color be enabled?
+    pub fn enabled(&self) -> bool {
+        self.level.has_color()
+    }
+
+    /// Should non-color text attributes, such as bold and underline, be
+    /// enabled?
+    ///
+    /// Always true when color is enabled.
+    pub fn styles_enabled(&self) -> bool {
+        self.styles || self.enabled()
+    }
+
+    /// Which [`ColorLevel`] should be used?
+    pub fn level(&self) -> ColorLevel {
+        self.level
+    }
+
+    /// The rule which decided the outcome.
+    pub fn reason(&self) -> Reason {
+        self.reason
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.enabled() {
+            write!(f, "color enabled ({}): {}", self.level, self.reason)
+        } else {
+            write!(f, "color disabled: {}", self.reason)
+        }
+    }
+}
+
+/// Why color was enabled or disabled.
+///
+/// The [`Display`](fmt::Display) implementation explains the reason.
+///
+/// # Example
+///
+/// ```rust
+/// use color_nope::{ColorNope, FakeTerminal, Reason, Stream};
+///
+/// let decision = ColorNope::new(Some("dumb".into()), None, None)
+///     .with_probe(FakeTerminal::all())
+///     .decide(Stream::Stdout);
+/// assert_eq!(decision.reason(), Reason::TermDumb);
+/// assert_eq!(decision.reason().to_string(), "TERM is set to dumb");
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Reason {
+    /// Color was forced on using [`Force::On`](crate::Force::On).
+    ForcedOn,
+    /// Color was forced off using [`Force::Off`](crate::Force::Off).
+    ForcedOff,
+    /// `NO_COLOR` is set to a non-empty value.
+    NoColor,
+    /// `FORCE_COLOR` is set.
+    ForceColor(ForceColor),
+    /// `CLICOLOR_FORCE` is set to a value other than `0`.
+    CliColorForce,
+    /// `CLICOLOR` is set to `0`.
+    CliColorOff,
+    /// The stream is not a terminal, but the [`CiProvider`] renders ANSI
+    /// colors in its logs.
+    Ci(CiProvider),
+    /// The stream is not a terminal.
+    NotATerminal,
+    /// `TERM` is not set.
+    TermUnset,
+    /// `TERM` is set to `dumb`.
+    TermDumb,
+    /// The terminfo entry for `TERM` supports fewer than 8 colors.
+    TerminfoNoColors,
+    /// The stream is a terminal, and nothing disabled color.
+    Terminal,
+    /// `RUST_LOG_STYLE` is set to `always`, see the
+    /// [`env_logger`](crate::env_logger) module.
+    #[cfg(feature = "env_logger")]
+    RustLogStyleAlways,
+    /// `RUST_LOG_STYLE` is set to `never`, see the
+    /// [`env_logger`](crate::env_logger) module.
+    #[cfg(feature = "env_logger")]
+    RustLogStyleNever,
+}
+
+impl Reason {
+    /// Does this reason result in color being enabled?
+    pub fn enables_color(&self) -> bool {
+        use Reason::*;
+        match self {
+            ForcedOn | CliColorForce | Terminal => true,
+            #[cfg(feature = "env_logger")]
+            RustLogStyleAlways => true,
+            ForceColor(force_color) => force_color.level().has_color(),
+            Ci(ci) => ci.color_level().has_color(),
+            ForcedOff | NoColor | CliColorOff | NotATerminal | TermUnset | TermDumb
+            | TerminfoNoColors => false,
+            #[cfg(feature = "env_logger")]
+            RustLogStyleNever => false,
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Reason::*;
+        match self {
+            ForcedOn => f.write_str("color was forced on"),
+            ForcedOff => f.write_str("color was forced off"),
+            NoColor => f.write_str("NO_COLOR is set"),
+            ForceColor(crate::ForceColor::Off) => f.write_str("FORCE_COLOR disables color"),
+            ForceColor(crate::ForceColor::On(level)) => {
+                write!(f, "FORCE_COLOR requests {level}")
+            }
+            CliColorForce => f.write_str("CLICOLOR_FORCE is set"),
+            CliColorOff => f.write_str("CLICOLOR is set to 0"),
+            Ci(ci) => write!(f, "the output is not a terminal, but {ci} renders color"),
+            NotATerminal => f.write_str("the output is not a terminal"),
+            TermUnset => f.write_str("TERM is not set"),
+            TermDumb => f.write_str("TERM is set to dumb"),
+            TerminfoNoColors => f.write_str("the terminfo entry for TERM has no colors"),
+            Terminal => f.write_str("the output is a terminal"),
+            #[cfg(feature = "env_logger")]
+            RustLogStyleAlways => f.write_str("RUST_LOG_STYLE is set to always"),
+            #[cfg(feature = "env_logger")]
+            RustLogStyleNever => f.write_str("RUST_LOG_STYLE is set to never"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        CiProvider, ColorLevel, ColorNope, FakeTerminal, Force, ForceColor, Reason, Stream,
+    };
+
+    fn tty(term: Option<&str>) -> ColorNope {
+        ColorNope::new(term.map(Into::into), None, None).with_probe(FakeTerminal::all())
+    }
+
+    fn xterm() -> ColorNope {
+        tty(Some("xterm"))
+    }
+
+    fn force_color(value: &str) -> ColorNope {
+        xterm().with_force_color_env(Some(value.into())).unwrap()
+    }
+
+    #[test]
+    fn every_reason() {
+        let mut cases = vec![
+            (
+                xterm().with_force(Some(Force::On)),
+                Reason::ForcedOn,
+                "color was forced on",
+            ),
+            (
+                xterm().with_force(Some(Force::Off)),
+                Reason::ForcedOff,
+                "color was forced off",
+            ),
+            (
+                ColorNope::new(Some("xterm".into()), Some("1".into()), None)
+                    .with_probe(FakeTerminal::all()),
+                Reason::NoColor,
+                "NO_COLOR is set",
+            ),
+            (
+                force_color("0"),
+                Reason::ForceColor(ForceColor::Off),
+                "FORCE_COLOR disables color",
+            ),
+            (
+                force_color("3"),
+                Reason::ForceColor(ForceColor::On(ColorLevel::TrueColor)),
+                "FORCE_COLOR requests 24-bit color",
+            ),
+            (
+                xterm().with_clicolor_force(Some("1".into())),
+                Reason::CliColorForce,
+                "CLICOLOR_FORCE is set",
+            ),
+            (
+                xterm().with_clicolor(Some("0".into())),
+                Reason::CliColorOff,
+                "CLICOLOR is set to 0",
+            ),
+            (
+                xterm()
+                    .with_probe(FakeTerminal::none())
+                    .with_ci(Some(CiProvider::GitLab)),
+                Reason::Ci(CiProvider::GitLab),
+                "the output is not a terminal, but GitLab CI renders color",
+            ),
+            (
+                xterm().with_probe(FakeTerminal::none()),
+                Reason::NotATerminal,
+                "the output is not a terminal",
+            ),
+            (tty(Some("dumb")), Reason::TermDumb, "TERM is set to dumb"),
+            (xterm(), Reason::Terminal, "the output is a terminal"),
+        ];
+        // An unset TERM doesn't disable color on Windows.
+        if cfg!(not(windows)) {
+            cases.push((tty(None), Reason::TermUnset, "TERM is not set"));
+        }
+
+        for (color, reason, explanation) in cases {
+            let decision = color.decide(Stream::Stdout);
+            assert_eq!(decision.reason(), reason);
+            assert_eq!(decision.reason().to_string(), explanation);
+            assert_eq!(decision.enabled(), reason.enables_color());
+            assert!(decision.to_string().ends_with(explanation));
+        }
+    }
+
+    #[test]
+    fn terminfo_no_colors() {
+        assert_eq!(
+            Reason::TerminfoNoColors.to_string(),
+            "the terminfo entry for TERM has no colors"
+        );
+    }
+
+    #[test]
+    fn decision_display() {
+        assert_eq!(
+            xterm().decide(Stream::Stdout).to_string(),
+            "color enabled (16 colors): the output is a terminal"
+        );
+    }
+}
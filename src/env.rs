@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::hash::BuildHasher;
+
+use crate::{ColorChoices, InvalidEnvVar};
+
+/// A source of environmental variables.
+///
+/// Implemented for the real process environment ([`ProcessEnv`]), for
+/// `HashMap<OsString, OsString>` and for closures of the form
+/// `Fn(&str) -> Option<OsString>`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use std::ffi::OsString;
+/// use color_nope::{ColorNope, EnvSource, FakeTerminal, Stream};
+///
+/// let mut env: HashMap<OsString, OsString> = HashMap::new();
+/// env.insert("TERM".into(), "xterm".into());
+/// env.insert("MYAPP_NO_COLOR".into(), "1".into());
+///
+/// let color = ColorNope::from_source(&env, Some("MYAPP"))
+///     .unwrap()
+///     .with_probe(FakeTerminal::all());
+/// assert_eq!(color.enable_color_for(Stream::Stdout), false);
+///
+/// // An empty MYAPP_NO_COLOR doesn't hide NO_COLOR.
+/// env.insert("NO_COLOR".into(), "1".into());
+/// env.insert("MYAPP_NO_COLOR".into(), "".into());
+/// let color = ColorNope::from_source(&env, Some("MYAPP"))
+///     .unwrap()
+///     .with_probe(FakeTerminal::all());
+/// assert_eq!(color.enable_color_for(Stream::Stdout), false);
+///
+/// let source = |key: &str| match key {
+///     "TERM" => Some(OsString::from("xterm")),
+///     "MYAPP_COLOR" => Some(OsString::from("always")),
+///     _ => None,
+/// };
+/// let color = ColorNope::from_source(&source, Some("MYAPP")).unwrap();
+/// assert_eq!(color.enable_color_for(Stream::Stdout), true);
+///
+/// let source = |key: &str| match key {
+///     "MYAPP_COLOR" => Some(OsString::from("never,stderr:always")),
+///     _ => None,
+/// };
+/// let color = ColorNope::from_source(&source, Some("MYAPP")).unwrap();
+/// assert_eq!(color.enable_color_for(Stream::Stdout), false);
+/// assert_eq!(color.enable_color_for(Stream::Stderr), true);
+/// ```
+pub trait EnvSource {
+    /// Get the value of the variable `key`, if set.
+    fn var_os(&self, key: &str) -> Option<OsString>;
+}
+
+/// The environment of the current process, read using [`std::env::var_os`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ProcessEnv;
+
+impl EnvSource for ProcessEnv {
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        std::env::var_os(key)
+    }
+}
+
+impl<S: BuildHasher> EnvSource for HashMap<OsString, OsString, S> {
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        self.get(OsStr::new(key)).cloned()
+    }
+}
+
+impl<F> EnvSource for F
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    fn var_os(&self, key: &str) -> Option<OsString> {
+        self(key)
+    }
+}
+
+/// The name of an app-specific variable, e.g. `MYAPP_NO_COLOR`.
+pub(crate) fn prefixed(prefix: Option<&str>, name: &str) -> Option<String> {
+    prefix.map(|prefix| format!("{}_{}", prefix.trim_end_matches('_'), name))
+}
+
+/// Read [`ColorChoices`] from the variable `name`. An empty value is treated
+/// as unset.
+pub(crate) fn color_choices_var(
+    source: &impl EnvSource,
+    name: &str,
+) -> Result<Option<ColorChoices>, InvalidEnvVar> {
+    let value = match source.var_os(name) {
+        Some(value) if !value.is_empty() => value,
+        _ => return Ok(None),
+    };
+    match value.to_str().map(str::parse) {
+        Some(Ok(choice)) => Ok(Some(choice)),
+        _ => Err(InvalidEnvVar {
+            name: name.to_owned(),
+            value,
+            expected: "auto, always or never, optionally per stream",
+        }),
+    }
+}
@@ -0,0 +1,99 @@
+//! Integration with [`env_logger`], which writes to stderr.
+//!
+//! `RUST_LOG_STYLE` can be given as an extra input. When set to `always` or
+//! `never` it takes precedence over everything except an explicit [`Force`]
+//! or stderr [`ColorChoice`]. Other values, such as `auto`, are ignored, as
+//! they are by `env_logger`. The resulting [`Decision`] then gives
+//! [`Reason::RustLogStyleAlways`] or [`Reason::RustLogStyleNever`]. Requires
+//! the `env_logger` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use color_nope::ColorNope;
+//!
+//! color_nope::env_logger::builder(&ColorNope::from_env(), std::env::var_os("RUST_LOG_STYLE"))
+//!     .parse_filters("info")
+//!     .init();
+//! ```
+//!
+//! [`Force`]: crate::Force
+//! [`Decision`]: crate::Decision
+//! [`Reason::RustLogStyleAlways`]: crate::Reason::RustLogStyleAlways
+//! [`Reason::RustLogStyleNever`]: crate::Reason::RustLogStyleNever
+
+use std::ffi::{OsStr, OsString};
+
+use ::env_logger::{Builder, Target, WriteStyle};
+
+use crate::{ColorChoice, ColorLevel, ColorNope, Decision, Reason, Stream};
+
+/// Decide whether `env_logger` should write styles to stderr, taking
+/// `RUST_LOG_STYLE` into account.
+///
+/// # Example
+///
+/// ```rust
+/// use std::ffi::OsStr;
+/// use color_nope::{ColorNope, Force, Reason};
+///
+/// let color = ColorNope::new(None, None, None);
+/// let decision = color_nope::env_logger::decide(&color, Some(OsStr::new("always")));
+/// assert_eq!(decision.enabled(), true);
+/// assert_eq!(decision.reason(), Reason::RustLogStyleAlways);
+/// assert_eq!(
+///     decision.to_string(),
+///     "color enabled (16 colors): RUST_LOG_STYLE is set to always"
+/// );
+///
+/// let decision = color_nope::env_logger::decide(&color, Some(OsStr::new("never")));
+/// assert_eq!(decision.to_string(), "color disabled: RUST_LOG_STYLE is set to never");
+///
+/// let color = ColorNope::new(None, None, Some(Force::Off));
+/// let decision = color_nope::env_logger::decide(&color, Some(OsStr::new("always")));
+/// assert_eq!(decision.enabled(), false);
+/// ```
+pub fn decide(color: &ColorNope, rust_log_style: Option<&OsStr>) -> Decision {
+    let style_choice = rust_log_style
+        .and_then(OsStr::to_str)
+        .and_then(|style| match style {
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        });
+    match style_choice {
+        _ if color.force_for(Stream::Stderr).is_some() => color.decide(Stream::Stderr),
+        Some(ColorChoice::Always) => {
+            let level = color
+                .clone()
+                .with_stream_choice(Stream::Stderr, ColorChoice::Always)
+                .color_level_for(Stream::Stderr);
+            Decision::new(level, Reason::RustLogStyleAlways)
+        }
+        Some(ColorChoice::Never) => Decision::new(ColorLevel::None, Reason::RustLogStyleNever),
+        _ => color.decide(Stream::Stderr),
+    }
+}
+
+/// Create an [`env_logger::Builder`] which writes to stderr, with
+/// its write style set using [`decide`].
+///
+/// Avoid calling `parse_env` or `parse_default_env` on the result, as they
+/// would replace the write style. Use `parse_filters` instead.
+pub fn builder(color: &ColorNope, rust_log_style: Option<OsString>) -> Builder {
+    let mut builder = Builder::new();
+    builder
+        .target(Target::Stderr)
+        .write_style(decide(color, rust_log_style.as_deref()).into());
+    builder
+}
+
+impl From<Decision> for WriteStyle {
+    fn from(decision: Decision) -> Self {
+        if decision.enabled() {
+            WriteStyle::Always
+        } else {
+            WriteStyle::Never
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! An application-wide [`ColorNope`], installed by the application for
+//! libraries to read.
+//!
+//! Libraries shouldn't read environmental variables themselves. Instead, the
+//! application makes a decision once and [`install`]s it. Libraries then call
+//! [`enabled_for`], which assumes color is off until something is installed.
+//!
+//! Only applications should call [`install`]. The slot can be set once, and
+//! is safe to read from any thread.
+//!
+//! # Example
+//!
+//! ```rust
+//! use color_nope::{global, ColorNope, Force, Stream};
+//!
+//! // In a library:
+//! assert_eq!(global::enabled_for(Stream::Stderr), false);
+//!
+//! // In the application's `main`:
+//! global::install(ColorNope::new(None, None, Some(Force::On))).unwrap();
+//! assert_eq!(global::enabled_for(Stream::Stderr), true);
+//!
+//! assert!(global::install(ColorNope::from_env()).is_err());
+//! ```
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::{ColorNope, Stream};
+
+static INSTALLED: OnceLock<ColorNope> = OnceLock::new();
+
+/// Install the application's [`ColorNope`].
+///
+/// Returns an error if one has already been installed.
+pub fn install(color: ColorNope) -> Result<(), AlreadyInstalled> {
+    INSTALLED.set(color).map_err(|_| AlreadyInstalled)
+}
+
+/// The installed [`ColorNope`], if any.
+pub fn get() -> Option<&'static ColorNope> {
+    INSTALLED.get()
+}
+
+/// Has the application installed a [`ColorNope`]?
+pub fn is_installed() -> bool {
+    INSTALLED.get().is_some()
+}
+
+/// Should color be enabled for the target stream?
+///
+/// Returns `false` if nothing has been installed.
+pub fn enabled_for(stream: Stream) -> bool {
+    INSTALLED
+        .get()
+        .is_some_and(|color| color.enable_color_for(stream))
+}
+
+/// Returned by [`install`] when a [`ColorNope`] has already been installed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AlreadyInstalled;
+
+impl fmt::Display for AlreadyInstalled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a global ColorNope has already been installed")
+    }
+}
+
+impl std::error::Error for AlreadyInstalled {}
@@ -0,0 +1,150 @@
+use std::ffi::OsString;
+
+use crate::{CiProvider, ColorChoice, ColorNope, FakeTerminal, Force, ForceColor, Stream};
+
+/// A snapshot of everything a [`ColorNope`] uses to make a decision.
+///
+/// Color bugs often depend on environmental variables and whether each stream
+/// is a terminal. Capture the inputs with [`ColorNope::inputs`] where the bug
+/// happens, and use [`ColorNope::replay`] to get the same decisions anywhere
+/// else.
+///
+/// Environmental variable values are stored as strings, converting any
+/// invalid Unicode lossily. With the `serde` feature the snapshot can be
+/// serialized, for example as JSON to attach to a bug report. Missing fields
+/// take their default values when deserializing.
+///
+/// # Example
+///
+/// ```rust
+/// use color_nope::{ColorNope, FakeTerminal, Stream};
+///
+/// let color = ColorNope::new(Some("xterm-256color".into()), None, None)
+///     .with_probe(FakeTerminal::new(true, false));
+/// let inputs = color.inputs();
+/// assert_eq!(inputs.term.as_deref(), Some("xterm-256color"));
+/// assert_eq!(inputs.stdout_is_terminal, true);
+///
+/// let replayed = ColorNope::replay(&inputs);
+/// for stream in [Stream::Stdout, Stream::Stderr] {
+///     assert_eq!(replayed.decide(stream), color.decide(stream));
+/// }
+/// ```
+///
+/// With the `serde` feature:
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use color_nope::{ColorInputs, ColorNope, Reason, Stream};
+///
+/// let inputs: ColorInputs = serde_json::from_str(r#"{
+///     "term": "xterm",
+///     "no_color": "1",
+///     "stdout_is_terminal": true
+/// }"#).unwrap();
+///
+/// let decision = ColorNope::replay(&inputs).decide(Stream::Stdout);
+/// assert_eq!(decision.reason(), Reason::NoColor);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[non_exhaustive]
+pub struct ColorInputs {
+    /// `TERM`.
+    pub term: Option<String>,
+    /// `NO_COLOR`, or the app-specific replacement.
+    pub no_color: Option<String>,
+    /// `CLICOLOR`.
+    pub clicolor: Option<String>,
+    /// `CLICOLOR_FORCE`.
+    pub clicolor_force: Option<String>,
+    /// `FORCE_COLOR`, once parsed.
+    pub force_color: Option<ForceColor>,
+    /// `COLORTERM`.
+    pub colorterm: Option<String>,
+    /// `TERM_PROGRAM`.
+    pub term_program: Option<String>,
+    /// `COLORFGBG`.
+    pub colorfgbg: Option<String>,
+    /// The [`Force`] override for both streams.
+    pub force: Option<Force>,
+    /// The [`ColorChoice`] for stdout.
+    pub stdout_choice: Option<ColorChoice>,
+    /// The [`ColorChoice`] for stderr.
+    pub stderr_choice: Option<ColorChoice>,
+    /// The number of colors reported by terminfo.
+    pub terminfo_colors: Option<u32>,
+    /// The detected CI provider.
+    pub ci: Option<CiProvider>,
+    /// Is stdout a terminal?
+    pub stdout_is_terminal: bool,
+    /// Is stderr a terminal?
+    pub stderr_is_terminal: bool,
+    /// Were the inputs captured on Windows, where an unset `TERM` doesn't
+    /// disable color?
+    pub windows: bool,
+}
+
+impl ColorNope {
+    /// Capture the inputs used to make decisions, including whether each
+    /// stream is currently a terminal.
+    ///
+    /// See [`ColorInputs`] for an example.
+    pub fn inputs(&self) -> ColorInputs {
+        let string = |value: &Option<OsString>| {
+            value
+                .as_ref()
+                .map(|value| value.to_string_lossy().into_owned())
+        };
+        ColorInputs {
+            term: string(&self.term_env),
+            no_color: string(&self.no_color_env),
+            clicolor: string(&self.clicolor_env),
+            clicolor_force: string(&self.clicolor_force_env),
+            force_color: self.force_color_env,
+            colorterm: string(&self.colorterm_env),
+            term_program: string(&self.term_program_env),
+            colorfgbg: string(&self.colorfgbg_env),
+            force: self.force_color,
+            stdout_choice: self.stdout_choice,
+            stderr_choice: self.stderr_choice,
+            terminfo_colors: self.terminfo_colors,
+            ci: self.ci,
+            stdout_is_terminal: self.probe.is_terminal(Stream::Stdout),
+            stderr_is_terminal: self.probe.is_terminal(Stream::Stderr),
+            windows: self.windows,
+        }
+    }
+
+    /// Recreate a [`ColorNope`] from captured inputs, making the same
+    /// decisions for [`Stream`]s regardless of the current environment.
+    ///
+    /// See [`ColorInputs`] for an example.
+    pub fn replay(inputs: &ColorInputs) -> ColorNope {
+        let os_string = |value: &Option<String>| value.as_ref().map(OsString::from);
+        let mut color = ColorNope::new(
+            os_string(&inputs.term),
+            os_string(&inputs.no_color),
+            inputs.force,
+        )
+        .with_clicolor(os_string(&inputs.clicolor))
+        .with_clicolor_force(os_string(&inputs.clicolor_force))
+        .with_colorterm(os_string(&inputs.colorterm))
+        .with_term_program(os_string(&inputs.term_program))
+        .with_colorfgbg(os_string(&inputs.colorfgbg))
+        .with_ci(inputs.ci)
+        .with_probe(FakeTerminal::new(
+            inputs.stdout_is_terminal,
+            inputs.stderr_is_terminal,
+        ));
+        color.force_color_env = inputs.force_color;
+        color.stdout_choice = inputs.stdout_choice;
+        color.stderr_choice = inputs.stderr_choice;
+        color.terminfo_colors = inputs.terminfo_colors;
+        color.windows = inputs.windows;
+        color
+    }
+}
@@ -0,0 +1,88 @@
+//! Conversions from a [`Decision`] into the color settings of other crates.
+//!
+//! This way a single decision, made by the application, can drive every
+//! library which writes colored output.
+
+use crate::Decision;
+
+/// Requires the `termcolor` feature.
+///
+/// ```rust
+/// use color_nope::{ColorNope, Force, Stream};
+///
+/// let choice: termcolor::ColorChoice = ColorNope::new(None, None, Some(Force::Off))
+///     .decide(Stream::Stdout)
+///     .into();
+/// assert_eq!(choice, termcolor::ColorChoice::Never);
+/// ```
+#[cfg(feature = "termcolor")]
+impl From<Decision> for termcolor::ColorChoice {
+    fn from(decision: Decision) -> Self {
+        if decision.enabled() {
+            termcolor::ColorChoice::Always
+        } else {
+            termcolor::ColorChoice::Never
+        }
+    }
+}
+
+/// Requires the `anstream` feature.
+///
+/// ```rust
+/// use color_nope::{ColorNope, Force, Stream};
+///
+/// let choice: anstream::ColorChoice = ColorNope::new(None, None, Some(Force::On))
+///     .decide(Stream::Stdout)
+///     .into();
+/// assert_eq!(choice, anstream::ColorChoice::Always);
+/// ```
+#[cfg(feature = "anstream")]
+impl From<Decision> for anstream::ColorChoice {
+    fn from(decision: Decision) -> Self {
+        if decision.enabled() {
+            anstream::ColorChoice::Always
+        } else {
+            anstream::ColorChoice::Never
+        }
+    }
+}
+
+/// Requires the `yansi` feature.
+///
+/// ```rust
+/// use color_nope::{ColorNope, Force, Stream};
+///
+/// let condition: yansi::Condition = ColorNope::new(None, None, Some(Force::On))
+///     .decide(Stream::Stdout)
+///     .into();
+/// yansi::whenever(condition);
+/// ```
+#[cfg(feature = "yansi")]
+impl From<Decision> for yansi::Condition {
+    fn from(decision: Decision) -> Self {
+        if decision.enabled() {
+            yansi::Condition::ALWAYS
+        } else {
+            yansi::Condition::NEVER
+        }
+    }
+}
+
+impl Decision {
+    /// Apply this decision to `owo-colors` using
+    /// [`owo_colors::set_override`].
+    ///
+    /// Requires the `owo-colors` feature.
+    ///
+    /// ```rust
+    /// use color_nope::{ColorNope, Force, Stream};
+    ///
+    /// ColorNope::new(None, None, Some(Force::Off))
+    ///     .decide(Stream::Stdout)
+    ///     .set_owo_colors_override();
+    /// ```
+    #[cfg(feature = "owo-colors")]
+    pub fn set_owo_colors_override(&self) {
+        owo_colors::set_override(self.enabled());
+    }
+}
@@ -7,6 +7,19 @@ the [Command Line Interface Guidelines](https://clig.dev/#output).
 ## Usage
 
 See [`ColorNope`] for usage examples.
+
+## Features
+
+- `clap`: provides `ColorArgs`, ready-made `--color` and `--no-color`
+  arguments.
+- `termcolor`, `anstream`, `owo-colors` and `yansi`: convert a [`Decision`]
+  into the color settings of each crate.
+- `env_logger`: maps a [`Decision`] for stderr into `env_logger`'s
+  `WriteStyle`, see the `env_logger` module.
+- `tracing`: configures `tracing-subscriber`'s `fmt` layer, see the
+  `tracing` module.
+- `serde`: serializes [`ColorInputs`] snapshots, along with the types they
+  contain.
 */
 
 #![deny(missing_docs)]
@@ -16,13 +29,71 @@ use doc_comment::doctest;
 #[cfg(doctest)]
 doctest!("../README.md");
 
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::io::IsTerminal;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod background;
+mod builder;
+mod choice;
+mod ci;
+#[cfg(feature = "clap")]
+mod clap_args;
+mod command;
+mod decision;
+mod env;
+#[cfg(feature = "env_logger")]
+pub mod env_logger;
+pub mod global;
+mod inputs;
+mod interop;
+pub mod palette;
+mod probe;
+mod strip;
+mod style;
+mod terminfo;
+#[cfg(feature = "tracing")]
+pub mod tracing;
+
+pub use background::Background;
+pub use builder::{BuildError, ColorNopeBuilder};
+pub use choice::{ColorChoice, ColorChoices, ParseColorChoiceError};
+pub use ci::CiProvider;
+#[cfg(feature = "clap")]
+pub use clap_args::ColorArgs;
+pub use decision::{Decision, Reason};
+pub use env::{EnvSource, ProcessEnv};
+pub use inputs::ColorInputs;
+pub use probe::{FakeTerminal, StdTerminal, TerminalProbe};
+pub use strip::StripWriter;
+pub use style::{Color, Style, Styled};
+pub use terminfo::{Terminfo, TerminfoError};
 
 /// Decides whether color should be enabled, based on the environment and the
 /// target stream.
 ///
 /// Assumes color is enabled by default, unless indicated otherwise.
 ///
+/// # Precedence
+///
+/// The first rule which applies decides the outcome:
+///
+/// 1. `force_color` ([`Force`]) turns color on or off. A [`ColorChoice`] set
+///    for a specific stream replaces it for that stream.
+/// 2. `FORCE_COLOR` ([`ForceColor`]) turns color off, or on at a given
+///    [`ColorLevel`]. As in Node.js, this overrides `NO_COLOR`.
+/// 3. A non-empty `NO_COLOR` turns color off.
+/// 4. A `CLICOLOR_FORCE` other than `0` turns color on, even when not writing
+///    to a terminal.
+/// 5. `CLICOLOR=0` turns color off.
+/// 6. When the stream is not a terminal, a [`CiProvider`] (if given) whose
+///    logs render ANSI colors turns color on at its level.
+/// 7. Otherwise color is on if the stream is a terminal and `TERM` allows it.
+///    If a [`Terminfo`] entry was given, it must also support at least 8
+///    colors.
+///
 /// # Examples
 ///
 /// Can be created using the `from_env()` convenience function:
@@ -60,6 +131,18 @@ pub struct ColorNope {
     term_env: Option<OsString>,
     no_color_env: Option<OsString>,
     force_color: Option<Force>,
+    stdout_choice: Option<ColorChoice>,
+    stderr_choice: Option<ColorChoice>,
+    colorterm_env: Option<OsString>,
+    term_program_env: Option<OsString>,
+    clicolor_env: Option<OsString>,
+    clicolor_force_env: Option<OsString>,
+    force_color_env: Option<ForceColor>,
+    terminfo_colors: Option<u32>,
+    ci: Option<CiProvider>,
+    colorfgbg_env: Option<OsString>,
+    probe: Arc<dyn TerminalProbe>,
+    windows: bool,
 }
 
 impl ColorNope {
@@ -89,29 +172,532 @@ impl ColorNope {
             term_env,
             no_color_env,
             force_color,
+            stdout_choice: None,
+            stderr_choice: None,
+            colorterm_env: None,
+            term_program_env: None,
+            clicolor_env: None,
+            clicolor_force_env: None,
+            force_color_env: None,
+            terminfo_colors: None,
+            ci: None,
+            colorfgbg_env: None,
+            probe: Arc::new(StdTerminal),
+            windows: cfg!(windows),
         }
     }
 
-    /// Uses the `TERM` and `NO_COLOR` environmental variables.
+    /// Create a [`ColorNopeBuilder`], to set each input by name.
+    ///
+    /// Prefer this to [`new`](ColorNope::new) when using more than the `TERM`
+    /// and `NO_COLOR` inputs.
+    pub fn builder() -> ColorNopeBuilder {
+        ColorNopeBuilder::default()
+    }
+
+    /// Uses the `TERM`, `NO_COLOR`, `FORCE_COLOR`, `CLICOLOR`,
+    /// `CLICOLOR_FORCE`, `COLORTERM`, `TERM_PROGRAM` and `COLORFGBG`
+    /// environmental variables.
+    ///
+    /// An invalid `FORCE_COLOR` value is silently ignored. Prefer
+    /// [`try_from_env`](ColorNope::try_from_env), which reports it.
     pub fn from_env() -> ColorNope {
-        ColorNope {
-            term_env: std::env::var_os("TERM"),
-            no_color_env: std::env::var_os("NO_COLOR"),
-            force_color: None,
+        let color = ColorNope::from_source_without_overrides(&ProcessEnv, None);
+        color
+            .clone()
+            .with_force_color_env(std::env::var_os("FORCE_COLOR"))
+            .unwrap_or(color)
+    }
+
+    /// Like [`from_env`](ColorNope::from_env), but returns an error if
+    /// `FORCE_COLOR` has an invalid value.
+    pub fn try_from_env() -> Result<ColorNope, InvalidEnvVar> {
+        ColorNope::from_source(&ProcessEnv, None)
+    }
+
+    /// Read the same variables as [`from_env`](ColorNope::from_env) through
+    /// an [`EnvSource`].
+    ///
+    /// With a `prefix` such as `MYAPP`, the app-specific variables
+    /// `MYAPP_NO_COLOR` and `MYAPP_COLOR` are also read:
+    ///
+    /// - `MYAPP_NO_COLOR`, if set to a non-empty value, is used instead of
+    ///   `NO_COLOR`.
+    /// - `MYAPP_COLOR` is parsed as [`ColorChoices`], such as `never` or
+    ///   `stderr:always,stdout:auto`, and applied using
+    ///   [`with_color_choices`](ColorNope::with_color_choices).
+    ///
+    /// Returns an error if `FORCE_COLOR` or `MYAPP_COLOR` has an invalid
+    /// value.
+    ///
+    /// See [`EnvSource`] for an example.
+    pub fn from_source(
+        source: &impl EnvSource,
+        prefix: Option<&str>,
+    ) -> Result<ColorNope, InvalidEnvVar> {
+        let choices = match env::prefixed(prefix, "COLOR") {
+            Some(name) => env::color_choices_var(source, &name)?,
+            None => None,
+        };
+        let color = ColorNope::from_source_without_overrides(source, prefix);
+        match choices {
+            Some(choices) => color.with_color_choices(choices),
+            None => color,
+        }
+        .with_force_color_env(source.var_os("FORCE_COLOR"))
+    }
+
+    fn from_source_without_overrides(source: &impl EnvSource, prefix: Option<&str>) -> ColorNope {
+        let no_color = env::prefixed(prefix, "NO_COLOR")
+            .and_then(|name| source.var_os(&name))
+            .filter(|value| !value.is_empty())
+            .or_else(|| source.var_os("NO_COLOR"));
+        ColorNope::new(source.var_os("TERM"), no_color, None)
+            .with_clicolor(source.var_os("CLICOLOR"))
+            .with_clicolor_force(source.var_os("CLICOLOR_FORCE"))
+            .with_colorterm(source.var_os("COLORTERM"))
+            .with_term_program(source.var_os("TERM_PROGRAM"))
+            .with_colorfgbg(source.var_os("COLORFGBG"))
+    }
+
+    /// Use the value of the `FORCE_COLOR` environmental variable.
+    ///
+    /// See [`ForceColor`] for the accepted values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::{ColorLevel, ColorNope, Stream};
+    ///
+    /// let color = ColorNope::new(None, None, None)
+    ///     .with_force_color_env(Some("2".into()))
+    ///     .unwrap();
+    /// assert_eq!(color.color_level_for(Stream::Stdout), ColorLevel::Ansi256);
+    ///
+    /// // FORCE_COLOR overrides NO_COLOR, as in Node.js.
+    /// let color = ColorNope::new(None, Some("1".into()), None)
+    ///     .with_force_color_env(Some("1".into()))
+    ///     .unwrap();
+    /// assert_eq!(color.enable_color_for(Stream::Stdout), true);
+    ///
+    /// let err = ColorNope::new(None, None, None)
+    ///     .with_force_color_env(Some("yes".into()))
+    ///     .unwrap_err();
+    /// assert_eq!(err.name(), "FORCE_COLOR");
+    /// ```
+    pub fn with_force_color_env(
+        mut self,
+        force_color_env: Option<OsString>,
+    ) -> Result<ColorNope, InvalidEnvVar> {
+        self.force_color_env = match force_color_env {
+            None => None,
+            Some(value) => Some(ForceColor::from_env_value(&value)?),
+        };
+        Ok(self)
+    }
+
+    /// Override other settings to force colors on or off.
+    pub fn with_force(mut self, force_color: Option<Force>) -> ColorNope {
+        self.force_color = force_color;
+        self
+    }
+
+    /// Use the `colors` capability of the terminal's [`Terminfo`] entry.
+    ///
+    /// When writing to a terminal, color is disabled if the entry supports
+    /// fewer than 8 colors. The entry can also raise the detected
+    /// [`ColorLevel`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::{ColorLevel, ColorNope, FakeTerminal, Reason, Stream, Terminfo};
+    ///
+    /// # fn entry(name: &str, colors: Option<u16>) -> Terminfo {
+    /// #     let mut bytes = vec![0x1a, 0x01, name.len() as u8 + 1, 0, 0, 0, 14, 0, 0, 0, 0, 0];
+    /// #     bytes.extend(name.as_bytes());
+    /// #     bytes.push(0);
+    /// #     bytes.extend([0u8].repeat(bytes.len() % 2));
+    /// #     bytes.extend([0xff, 0xff].repeat(13));
+    /// #     bytes.extend(colors.map_or([0xff, 0xff], u16::to_le_bytes));
+    /// #     Terminfo::parse(&bytes).unwrap()
+    /// # }
+    /// let vt100 = entry("vt100", None);
+    /// let decision = ColorNope::new(Some("vt100".into()), None, None)
+    ///     .with_terminfo(Some(&vt100))
+    ///     .with_probe(FakeTerminal::all())
+    ///     .decide(Stream::Stdout);
+    /// assert_eq!(decision.reason(), Reason::TerminfoNoColors);
+    ///
+    /// let xterm = entry("xterm-256color", Some(256));
+    /// let color = ColorNope::new(Some("xterm-256color".into()), None, None)
+    ///     .with_terminfo(Some(&xterm))
+    ///     .with_probe(FakeTerminal::all());
+    /// assert_eq!(color.color_level_for(Stream::Stdout), ColorLevel::Ansi256);
+    /// ```
+    pub fn with_terminfo(mut self, terminfo: Option<&Terminfo>) -> ColorNope {
+        self.terminfo_colors = terminfo.map(|terminfo| terminfo.colors().unwrap_or(0));
+        self
+    }
+
+    /// Enable color when not writing to a terminal, if the [`CiProvider`]
+    /// renders ANSI colors in its logs.
+    ///
+    /// See [`CiProvider`] for an example.
+    pub fn with_ci(mut self, ci: Option<CiProvider>) -> ColorNope {
+        self.ci = ci;
+        self
+    }
+
+    /// Use the value of the `COLORFGBG` environmental variable when
+    /// detecting the [`Background`].
+    pub fn with_colorfgbg(mut self, colorfgbg_env: Option<OsString>) -> ColorNope {
+        self.colorfgbg_env = colorfgbg_env;
+        self
+    }
+
+    /// Set a [`ColorChoice`] for one stream, replacing `force_color` for that
+    /// stream.
+    ///
+    /// [`ColorChoice::Auto`] lets the stream follow the environment, even if
+    /// `force_color` is set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::{ColorChoice, ColorNope, FakeTerminal, Force, Stream};
+    ///
+    /// let color = ColorNope::new(Some("xterm".into()), None, Some(Force::Off))
+    ///     .with_stream_choice(Stream::Stderr, ColorChoice::Always)
+    ///     .with_probe(FakeTerminal::all());
+    ///
+    /// assert_eq!(color.enable_color_for(Stream::Stdout), false);
+    /// assert_eq!(color.enable_color_for(Stream::Stderr), true);
+    /// ```
+    pub fn with_stream_choice(mut self, stream: Stream, choice: ColorChoice) -> ColorNope {
+        match stream {
+            Stream::Stdout => self.stdout_choice = Some(choice),
+            Stream::Stderr => self.stderr_choice = Some(choice),
         }
+        self
+    }
+
+    /// Apply [`ColorChoices`], e.g. from `--color=stderr:always,stdout:auto`.
+    ///
+    /// The default choice replaces `force_color`, and any per-stream choices
+    /// are set using [`with_stream_choice`](ColorNope::with_stream_choice).
+    pub fn with_color_choices(mut self, choices: ColorChoices) -> ColorNope {
+        self.force_color = choices.default_choice().force();
+        self.stdout_choice = choices.stream_choice(Stream::Stdout);
+        self.stderr_choice = choices.stream_choice(Stream::Stderr);
+        self
+    }
+
+    /// Use the given [`TerminalProbe`] to check whether a stream is a
+    /// terminal, instead of [`StdTerminal`].
+    pub fn with_probe(mut self, probe: impl TerminalProbe + 'static) -> ColorNope {
+        self.probe = Arc::new(probe);
+        self
+    }
+
+    /// Use the value of the `CLICOLOR` environmental variable.
+    ///
+    /// `CLICOLOR=0` disables color. See the [CLICOLOR
+    /// spec](https://bixense.com/clicolors/).
+    pub fn with_clicolor(mut self, clicolor_env: Option<OsString>) -> ColorNope {
+        self.clicolor_env = clicolor_env;
+        self
+    }
+
+    /// Use the value of the `CLICOLOR_FORCE` environmental variable.
+    ///
+    /// Any non-empty value other than `0` enables color, even when the stream
+    /// is not a terminal. `NO_COLOR` still takes precedence.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::{ColorNope, Stream};
+    ///
+    /// let color = ColorNope::new(None, None, None).with_clicolor_force(Some("1".into()));
+    /// assert_eq!(color.enable_color_for(Stream::Stdout), true);
+    ///
+    /// let color = ColorNope::new(None, Some("1".into()), None)
+    ///     .with_clicolor_force(Some("1".into()));
+    /// assert_eq!(color.enable_color_for(Stream::Stdout), false);
+    /// ```
+    pub fn with_clicolor_force(mut self, clicolor_force_env: Option<OsString>) -> ColorNope {
+        self.clicolor_force_env = clicolor_force_env;
+        self
+    }
+
+    /// Use the value of the `COLORTERM` environmental variable when detecting
+    /// the [`ColorLevel`].
+    pub fn with_colorterm(mut self, colorterm_env: Option<OsString>) -> ColorNope {
+        self.colorterm_env = colorterm_env;
+        self
+    }
+
+    /// Use the value of the `TERM_PROGRAM` environmental variable when
+    /// detecting the [`ColorLevel`].
+    pub fn with_term_program(mut self, term_program_env: Option<OsString>) -> ColorNope {
+        self.term_program_env = term_program_env;
+        self
     }
 
     /// Should color be enabled for the target stream?
+    ///
+    /// See [Precedence](ColorNope#precedence) for the order in which the
+    /// inputs are considered.
     pub fn enable_color_for(&self, stream: Stream) -> bool {
-        match self.force_color {
-            Some(force) => force.enable_color(),
-            None => {
-                atty::is(stream.into())
-                    && term_allows_color(self.term_env.as_ref())
-                    && no_color_allows_color(self.no_color_env.as_ref())
-            }
+        self.decide(stream).enabled()
+    }
+
+    /// Should non-color text attributes, such as bold, italic and underline,
+    /// be enabled for the target stream?
+    ///
+    /// This is true whenever color is enabled. The [NO_COLOR] spec only asks
+    /// for color to be disabled, so this is also true for a terminal with a
+    /// `TERM` other than `dumb` when `NO_COLOR` is set.
+    ///
+    /// [NO_COLOR]: https://no-color.org/
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::{ColorNope, FakeTerminal, Stream};
+    ///
+    /// let color = ColorNope::new(Some("xterm".into()), Some("1".into()), None)
+    ///     .with_probe(FakeTerminal::all());
+    /// assert_eq!(color.enable_color_for(Stream::Stdout), false);
+    /// assert_eq!(color.enable_styles_for(Stream::Stdout), true);
+    ///
+    /// let color = ColorNope::new(Some("dumb".into()), Some("1".into()), None)
+    ///     .with_probe(FakeTerminal::all());
+    /// assert_eq!(color.enable_styles_for(Stream::Stdout), false);
+    /// ```
+    pub fn enable_styles_for(&self, stream: Stream) -> bool {
+        self.decide(stream).styles_enabled()
+    }
+
+    /// Which [`ColorLevel`] should be used for the target stream?
+    ///
+    /// Returns [`ColorLevel::None`] whenever color is disabled. When
+    /// `FORCE_COLOR` decides the outcome it also sets the level. Otherwise the
+    /// level is detected from `COLORTERM`, the suffix of `TERM` and
+    /// `TERM_PROGRAM`, falling back to [`ColorLevel::Basic16`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::{ColorLevel, ColorNope, Force, Stream};
+    ///
+    /// let color = ColorNope::new(Some("xterm-256color".into()), None, Some(Force::On));
+    /// assert_eq!(color.color_level_for(Stream::Stdout), ColorLevel::Ansi256);
+    ///
+    /// let color = color.with_colorterm(Some("truecolor".into()));
+    /// assert_eq!(color.color_level_for(Stream::Stdout), ColorLevel::TrueColor);
+    ///
+    /// let color = ColorNope::new(Some("xterm-256color".into()), None, Some(Force::Off));
+    /// assert_eq!(color.color_level_for(Stream::Stdout), ColorLevel::None);
+    /// ```
+    pub fn color_level_for(&self, stream: Stream) -> ColorLevel {
+        self.decide(stream).level()
+    }
+
+    /// Decide whether color should be enabled for the target stream, and
+    /// explain why.
+    ///
+    /// See [`Decision`] for an example.
+    pub fn decide(&self, stream: Stream) -> Decision {
+        self.decide_with(self.force_for(stream), || self.probe.is_terminal(stream))
+    }
+
+    /// The [`Force`] override for a stream, taking per-stream choices into
+    /// account.
+    fn force_for(&self, stream: Stream) -> Option<Force> {
+        let stream_choice = match stream {
+            Stream::Stdout => self.stdout_choice,
+            Stream::Stderr => self.stderr_choice,
+        };
+        match stream_choice {
+            Some(choice) => choice.force(),
+            None => self.force_color,
+        }
+    }
+
+    /// Detect whether the terminal has a light or dark background.
+    ///
+    /// Returns [`Background::Unknown`] if color is disabled for the target
+    /// stream. Otherwise `COLORFGBG` is used if it is set.
+    ///
+    /// If that doesn't give an answer and `query_timeout` is given, the
+    /// controlling terminal is asked for its background color using an OSC 11
+    /// query, waiting up to `query_timeout` for a response. This is only
+    /// supported on Unix. The query temporarily changes the terminal's mode,
+    /// so avoid it while something else is reading from the terminal.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::{Background, ColorNope, Force, Stream};
+    ///
+    /// let color = ColorNope::new(None, None, Some(Force::On))
+    ///     .with_colorfgbg(Some("0;15".into()));
+    /// assert_eq!(color.background_for(Stream::Stdout, None), Background::Light);
+    ///
+    /// let color = color.with_force(Some(Force::Off));
+    /// assert_eq!(color.background_for(Stream::Stdout, None), Background::Unknown);
+    /// ```
+    pub fn background_for(&self, stream: Stream, query_timeout: Option<Duration>) -> Background {
+        if !self.enable_color_for(stream) {
+            return Background::Unknown;
+        }
+        let from_env = self
+            .colorfgbg_env
+            .as_deref()
+            .map_or(Background::Unknown, Background::from_colorfgbg);
+        match (from_env, query_timeout) {
+            (Background::Unknown, Some(timeout)) => background::query_osc11(timeout),
+            (background, _) => background,
         }
     }
+
+    /// Should color be enabled for an arbitrary handle, such as a [`File`],
+    /// `/dev/tty` or an inherited file descriptor?
+    ///
+    /// The same rules as [`enable_color_for`](ColorNope::enable_color_for)
+    /// apply, except that the handle itself is checked instead of consulting
+    /// the [`TerminalProbe`]. On Unix, inherited file descriptors can be
+    /// checked by borrowing them as a `BorrowedFd`.
+    ///
+    /// [`File`]: std::fs::File
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::{ColorNope, Force};
+    ///
+    /// let file = tempfile();
+    /// let color = ColorNope::new(Some("xterm".into()), None, None);
+    /// assert_eq!(color.enable_color_for_handle(&file), false);
+    ///
+    /// let color = ColorNope::new(Some("xterm".into()), None, Some(Force::On));
+    /// assert_eq!(color.enable_color_for_handle(&file), true);
+    /// # fn tempfile() -> std::fs::File {
+    /// #     let path = std::env::temp_dir().join("color-nope-handle-doctest");
+    /// #     std::fs::File::create(path).unwrap()
+    /// # }
+    /// ```
+    pub fn enable_color_for_handle<H: IsTerminal>(&self, handle: &H) -> bool {
+        self.decide_for_handle(handle).enabled()
+    }
+
+    /// Should non-color text attributes be enabled for an arbitrary handle?
+    ///
+    /// See [`enable_styles_for`](ColorNope::enable_styles_for).
+    pub fn enable_styles_for_handle<H: IsTerminal>(&self, handle: &H) -> bool {
+        self.decide_for_handle(handle).styles_enabled()
+    }
+
+    /// Which [`ColorLevel`] should be used for an arbitrary handle?
+    ///
+    /// See [`enable_color_for_handle`](ColorNope::enable_color_for_handle).
+    pub fn color_level_for_handle<H: IsTerminal>(&self, handle: &H) -> ColorLevel {
+        self.decide_for_handle(handle).level()
+    }
+
+    /// Decide whether color should be enabled for an arbitrary handle, and
+    /// explain why.
+    pub fn decide_for_handle<H: IsTerminal>(&self, handle: &H) -> Decision {
+        self.decide_with(self.force_color, || handle.is_terminal())
+    }
+
+    fn decide_with(&self, force: Option<Force>, is_terminal: impl FnOnce() -> bool) -> Decision {
+        let reason = if let Some(force) = force {
+            match force {
+                Force::On => Reason::ForcedOn,
+                Force::Off => Reason::ForcedOff,
+            }
+        } else if let Some(force_color) = self.force_color_env {
+            return Decision::new(force_color.level(), Reason::ForceColor(force_color));
+        } else if !no_color_allows_color(self.no_color_env.as_ref()) {
+            // NO_COLOR only disables color, other attributes are still fine.
+            let styles = is_terminal() && term_allows_color(self.term_env.as_ref(), self.windows);
+            return Decision::new(ColorLevel::None, Reason::NoColor).with_styles(styles);
+        } else if clicolor_force_enables_color(self.clicolor_force_env.as_ref()) {
+            Reason::CliColorForce
+        } else if !clicolor_allows_color(self.clicolor_env.as_ref()) {
+            Reason::CliColorOff
+        } else if !is_terminal() {
+            match self.ci {
+                Some(ci) if ci.color_level().has_color() => {
+                    return Decision::new(ci.color_level(), Reason::Ci(ci));
+                }
+                _ => Reason::NotATerminal,
+            }
+        } else if !term_allows_color(self.term_env.as_ref(), self.windows) {
+            match self.term_env {
+                None => Reason::TermUnset,
+                Some(_) => Reason::TermDumb,
+            }
+        } else if self.terminfo_colors.is_some_and(|colors| colors < 8) {
+            Reason::TerminfoNoColors
+        } else {
+            Reason::Terminal
+        };
+
+        let level = if reason.enables_color() {
+            let terminfo_level = match self.terminfo_colors {
+                Some(colors) if colors >= 1 << 24 => ColorLevel::TrueColor,
+                Some(colors) if colors >= 256 => ColorLevel::Ansi256,
+                _ => ColorLevel::Basic16,
+            };
+            detect_level(
+                self.term_env.as_ref(),
+                self.colorterm_env.as_ref(),
+                self.term_program_env.as_ref(),
+            )
+            .max(terminfo_level)
+        } else {
+            ColorLevel::None
+        };
+        Decision::new(level, reason).with_styles(reason == Reason::TerminfoNoColors)
+    }
+}
+
+/// How many colors can be displayed.
+///
+/// Levels are ordered, so `level >= ColorLevel::Ansi256` can be used to check
+/// for at least 256 colors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorLevel {
+    /// Color is disabled.
+    None,
+    /// The 16 basic ANSI colors.
+    Basic16,
+    /// The 256 color xterm palette.
+    Ansi256,
+    /// 24-bit RGB colors.
+    TrueColor,
+}
+impl ColorLevel {
+    /// Is any color enabled?
+    pub fn has_color(&self) -> bool {
+        *self != ColorLevel::None
+    }
+}
+impl fmt::Display for ColorLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ColorLevel::*;
+        f.write_str(match self {
+            None => "no color",
+            Basic16 => "16 colors",
+            Ansi256 => "256 colors",
+            TrueColor => "24-bit color",
+        })
+    }
 }
 
 /// Output streams.
@@ -122,57 +708,162 @@ pub enum Stream {
     #[allow(missing_docs)]
     Stderr,
 }
-impl From<Stream> for atty::Stream {
-    fn from(s: Stream) -> Self {
-        match s {
-            Stream::Stdout => atty::Stream::Stdout,
-            Stream::Stderr => atty::Stream::Stderr,
-        }
-    }
-}
 
 /// Override other settings to force colors on or off.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Force {
     #[allow(missing_docs)]
     On,
     #[allow(missing_docs)]
     Off,
 }
-impl Force {
-    fn enable_color(&self) -> bool {
-        use Force::*;
+
+/// A `FORCE_COLOR` value, as used in the Node.js ecosystem.
+///
+/// | Value                | Meaning                           |
+/// |----------------------|-----------------------------------|
+/// | `0`, `false`         | [`ForceColor::Off`]               |
+/// | `1`, `true`, empty   | On at [`ColorLevel::Basic16`]     |
+/// | `2`                  | On at [`ColorLevel::Ansi256`]     |
+/// | `3`                  | On at [`ColorLevel::TrueColor`]   |
+///
+/// As in Node.js, `FORCE_COLOR` overrides `NO_COLOR`. See
+/// [Precedence](ColorNope#precedence).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ForceColor {
+    /// Force color off.
+    Off,
+    /// Force color on at the given level.
+    On(ColorLevel),
+}
+impl ForceColor {
+    /// Parse a `FORCE_COLOR` value.
+    ///
+    /// As in Node.js, an empty value forces color on at
+    /// [`ColorLevel::Basic16`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::ffi::OsStr;
+    /// use color_nope::{ColorLevel, ForceColor};
+    ///
+    /// assert_eq!(
+    ///     ForceColor::from_env_value(OsStr::new("")),
+    ///     Ok(ForceColor::On(ColorLevel::Basic16))
+    /// );
+    /// assert_eq!(ForceColor::from_env_value(OsStr::new("0")), Ok(ForceColor::Off));
+    /// assert!(ForceColor::from_env_value(OsStr::new("4")).is_err());
+    /// ```
+    pub fn from_env_value(value: &OsStr) -> Result<ForceColor, InvalidEnvVar> {
+        match value.to_str() {
+            Some("0" | "false") => Ok(ForceColor::Off),
+            Some("" | "1" | "true") => Ok(ForceColor::On(ColorLevel::Basic16)),
+            Some("2") => Ok(ForceColor::On(ColorLevel::Ansi256)),
+            Some("3") => Ok(ForceColor::On(ColorLevel::TrueColor)),
+            _ => Err(InvalidEnvVar {
+                name: "FORCE_COLOR".to_owned(),
+                value: value.to_owned(),
+                expected: "0, 1, 2, 3, true or false",
+            }),
+        }
+    }
+
+    /// The resulting [`ColorLevel`].
+    pub fn level(&self) -> ColorLevel {
         match self {
-            On => true,
-            Off => false,
+            ForceColor::Off => ColorLevel::None,
+            ForceColor::On(level) => *level,
         }
     }
 }
 
+/// An environmental variable had a value which could not be understood.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidEnvVar {
+    name: String,
+    value: OsString,
+    expected: &'static str,
+}
+impl InvalidEnvVar {
+    /// The name of the variable.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The invalid value.
+    pub fn value(&self) -> &OsStr {
+        &self.value
+    }
+}
+impl fmt::Display for InvalidEnvVar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid value {:?} for {}, expected {}",
+            self.value, self.name, self.expected
+        )
+    }
+}
+impl std::error::Error for InvalidEnvVar {}
+
 fn no_color_allows_color(no_color: Option<&OsString>) -> bool {
-    no_color.map_or(true, |s| s.is_empty())
+    match no_color {
+        None => true,
+        Some(s) => s.is_empty(),
+    }
+}
+
+fn clicolor_allows_color(clicolor: Option<&OsString>) -> bool {
+    match clicolor {
+        None => true,
+        Some(s) => s != "0",
+    }
+}
+
+fn clicolor_force_enables_color(clicolor_force: Option<&OsString>) -> bool {
+    match clicolor_force {
+        None => false,
+        Some(s) => !s.is_empty() && s != "0",
+    }
+}
+
+fn detect_level(
+    term: Option<&OsString>,
+    colorterm: Option<&OsString>,
+    term_program: Option<&OsString>,
+) -> ColorLevel {
+    let term = term.and_then(|v| v.to_str()).unwrap_or_default();
+    let colorterm = colorterm.and_then(|v| v.to_str()).unwrap_or_default();
+    let term_program = term_program.and_then(|v| v.to_str()).unwrap_or_default();
+
+    if matches!(colorterm, "truecolor" | "24bit") || term.ends_with("-direct") {
+        return ColorLevel::TrueColor;
+    }
+    match term_program {
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty" => return ColorLevel::TrueColor,
+        "Apple_Terminal" => return ColorLevel::Ansi256,
+        _ => {}
+    }
+    if term.ends_with("-256color") || term.ends_with("-256") {
+        return ColorLevel::Ansi256;
+    }
+    ColorLevel::Basic16
 }
 
 // These next functions are shamelessly stolen from [termcolor](https://github.com/BurntSushi/termcolor).
 
-#[cfg(not(windows))]
-fn term_allows_color(term: Option<&OsString>) -> bool {
+fn term_allows_color(term: Option<&OsString>, windows: bool) -> bool {
     match term {
+        Some(v) => v != "dumb",
+        // On Windows, if TERM isn't set, then we shouldn't automatically
+        // assume that colors aren't allowed. This is unlike Unix environments
+        // where TERM is more rigorously set.
+        None if windows => true,
         // If TERM isn't set, then we are in a weird environment that
         // probably doesn't support colors.
         None => false,
-        Some(v) => v != "dumb",
-    }
-}
-
-#[cfg(windows)]
-fn term_allows_color(term: Option<&OsString>) -> bool {
-    // On Windows, if TERM isn't set, then we shouldn't automatically
-    // assume that colors aren't allowed. This is unlike Unix environments
-    // where TERM is more rigorously set.
-    if let Some(v) = term {
-        v != "dumb"
-    } else {
-        true
     }
 }
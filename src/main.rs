@@ -0,0 +1,104 @@
+//! Decide whether to use color from shell scripts.
+//!
+//! ```sh
+//! if color-nope check stdout; then
+//!     printf '\033[31merror\033[0m\n'
+//! else
+//!     printf 'error\n'
+//! fi
+//! ```
+
+use std::process::ExitCode;
+
+use color_nope::{ColorLevel, ColorNope, Stream};
+
+const USAGE: &str = "\
+Usage: color-nope <COMMAND> [STREAM]
+
+Decides whether to use color, based on NO_COLOR, FORCE_COLOR, CLICOLOR,
+CLICOLOR_FORCE, TERM and whether STREAM is a terminal.
+
+Commands:
+  check [STREAM]    Exit with 0 if color should be used, or 1 if not
+  level [STREAM]    Print the number of colors to use: 0, 16, 256 or 16777216
+  explain [STREAM]  Print the decision and the reason for it
+
+STREAM is stdout or stderr. `check` defaults to stdout, and `level` defaults
+to stderr, as its output is usually captured with $(...), which makes stdout
+a pipe. `explain` shows both streams if STREAM is not given, but the stdout
+decision is only meaningful when the output is not captured.
+";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let (command, stream) = match args.as_slice() {
+        ["-h" | "--help"] => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        ["-V" | "--version"] => {
+            println!("color-nope {}", env!("CARGO_PKG_VERSION"));
+            return ExitCode::SUCCESS;
+        }
+        [command] => (*command, None),
+        [command, stream] => match parse_stream(stream) {
+            Some(stream) => (*command, Some(stream)),
+            None => return usage_error(&format!("unknown stream `{stream}`")),
+        },
+        _ => return usage_error("expected a command"),
+    };
+
+    let color = match ColorNope::try_from_env() {
+        Ok(color) => color,
+        Err(err) => {
+            eprintln!("color-nope: {err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match command {
+        "check" => {
+            if color.enable_color_for(stream.unwrap_or(Stream::Stdout)) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        "level" => {
+            let colors = match color.color_level_for(stream.unwrap_or(Stream::Stderr)) {
+                ColorLevel::None => 0,
+                ColorLevel::Basic16 => 16,
+                ColorLevel::Ansi256 => 256,
+                ColorLevel::TrueColor => 1 << 24,
+            };
+            println!("{colors}");
+            ExitCode::SUCCESS
+        }
+        "explain" => {
+            match stream {
+                Some(stream) => println!("{}", color.decide(stream)),
+                None => {
+                    println!("stdout: {}", color.decide(Stream::Stdout));
+                    println!("stderr: {}", color.decide(Stream::Stderr));
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        _ => usage_error(&format!("unknown command `{command}`")),
+    }
+}
+
+fn parse_stream(stream: &str) -> Option<Stream> {
+    match stream {
+        "stdout" => Some(Stream::Stdout),
+        "stderr" => Some(Stream::Stderr),
+        _ => None,
+    }
+}
+
+fn usage_error(message: &str) -> ExitCode {
+    eprintln!("color-nope: {message}\n\n{USAGE}");
+    ExitCode::from(2)
+}
@@ -0,0 +1,141 @@
+//! Conversion of colors to the nearest color in a smaller palette.
+//!
+//! Used by [`Style`](crate::Style) to render colors at the [`ColorLevel`]
+//! the terminal supports, but can also be used directly.
+//!
+//! # Example
+//!
+//! ```rust
+//! use color_nope::palette::{self, Metric};
+//! use color_nope::Color;
+//!
+//! assert_eq!(palette::rgb_to_ansi256(255, 135, 0, Metric::Rgb), 208);
+//! assert_eq!(palette::rgb_to_basic16(250, 10, 10, Metric::Cielab), Color::BrightRed);
+//! ```
+//!
+//! [`ColorLevel`]: crate::ColorLevel
+
+use crate::Color;
+
+/// How the distance between two colors is measured.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Metric {
+    /// Euclidean distance between RGB values. Fast, but not perceptually
+    /// uniform.
+    #[default]
+    Rgb,
+    /// Euclidean distance in the CIELAB color space (CIE76), which better
+    /// matches how different colors look.
+    Cielab,
+}
+
+/// The basic 16 colors, using the xterm defaults. Terminal themes often
+/// change these.
+const BASIC16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::White, (229, 229, 229)),
+    (Color::BrightBlack, (127, 127, 127)),
+    (Color::BrightRed, (255, 0, 0)),
+    (Color::BrightGreen, (0, 255, 0)),
+    (Color::BrightYellow, (255, 255, 0)),
+    (Color::BrightBlue, (92, 92, 255)),
+    (Color::BrightMagenta, (255, 0, 255)),
+    (Color::BrightCyan, (0, 255, 255)),
+    (Color::BrightWhite, (255, 255, 255)),
+];
+
+/// The channel values used by the 6x6x6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The RGB value of an xterm-256 palette index.
+pub fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => BASIC16[usize::from(index)].1,
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[usize::from(i / 36)],
+                CUBE_LEVELS[usize::from(i / 6 % 6)],
+                CUBE_LEVELS[usize::from(i % 6)],
+            )
+        }
+        232..=255 => {
+            let gray = 8 + 10 * (index - 232);
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// The nearest xterm-256 palette index to an RGB color.
+///
+/// Only the color cube and grayscale ramp (16-255) are considered, as the
+/// first 16 colors are often changed by terminal themes.
+pub fn rgb_to_ansi256(r: u8, g: u8, b: u8, metric: Metric) -> u8 {
+    nearest(
+        (16..=255).map(|i| (i, ansi256_to_rgb(i))),
+        (r, g, b),
+        metric,
+    )
+}
+
+/// The nearest of the basic 16 colors to an RGB color.
+pub fn rgb_to_basic16(r: u8, g: u8, b: u8, metric: Metric) -> Color {
+    nearest(BASIC16.iter().copied(), (r, g, b), metric)
+}
+
+fn nearest<T: Copy>(
+    candidates: impl Iterator<Item = (T, (u8, u8, u8))>,
+    target: (u8, u8, u8),
+    metric: Metric,
+) -> T {
+    let target = to_space(target, metric);
+    candidates
+        .map(|(value, rgb)| (value, distance(target, to_space(rgb, metric))))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(value, _)| value)
+        .expect("palettes are not empty")
+}
+
+fn to_space((r, g, b): (u8, u8, u8), metric: Metric) -> [f64; 3] {
+    match metric {
+        Metric::Rgb => [f64::from(r), f64::from(g), f64::from(b)],
+        Metric::Cielab => rgb_to_lab(r, g, b),
+    }
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum()
+}
+
+/// Convert sRGB to CIELAB, using the D65 white point.
+fn rgb_to_lab(r: u8, g: u8, b: u8) -> [f64; 3] {
+    let linear = |c: u8| {
+        let c = f64::from(c) / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (linear(r), linear(g), linear(b));
+
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+    let f = |t: f64| {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
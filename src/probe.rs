@@ -0,0 +1,82 @@
+use std::fmt;
+use std::io::IsTerminal;
+
+use crate::Stream;
+
+/// Checks whether a [`Stream`] is connected to a terminal.
+///
+/// [`ColorNope`](crate::ColorNope) uses [`StdTerminal`] by default. Use
+/// [`FakeTerminal`] to test code paths which depend on a terminal being
+/// present.
+pub trait TerminalProbe: fmt::Debug + Send + Sync {
+    /// Is the stream connected to a terminal?
+    fn is_terminal(&self, stream: Stream) -> bool;
+}
+
+/// The default [`TerminalProbe`], using [`std::io::IsTerminal`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StdTerminal;
+
+impl TerminalProbe for StdTerminal {
+    fn is_terminal(&self, stream: Stream) -> bool {
+        match stream {
+            Stream::Stdout => std::io::stdout().is_terminal(),
+            Stream::Stderr => std::io::stderr().is_terminal(),
+        }
+    }
+}
+
+/// A [`TerminalProbe`] with fixed answers, for use in tests.
+///
+/// # Example
+///
+/// ```rust
+/// use color_nope::{ColorNope, FakeTerminal, Stream};
+///
+/// let color = ColorNope::new(Some("xterm".into()), None, None)
+///     .with_probe(FakeTerminal::new(true, false));
+///
+/// assert_eq!(color.enable_color_for(Stream::Stdout), true);
+/// assert_eq!(color.enable_color_for(Stream::Stderr), false);
+///
+/// let color = ColorNope::new(Some("dumb".into()), None, None).with_probe(FakeTerminal::all());
+/// assert_eq!(color.enable_color_for(Stream::Stdout), false);
+///
+/// let color = ColorNope::new(Some("xterm".into()), None, None)
+///     .with_clicolor(Some("0".into()))
+///     .with_probe(FakeTerminal::all());
+/// assert_eq!(color.enable_color_for(Stream::Stdout), false);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FakeTerminal {
+    /// Is stdout a terminal?
+    pub stdout: bool,
+    /// Is stderr a terminal?
+    pub stderr: bool,
+}
+
+impl FakeTerminal {
+    /// Create a probe with the given answers for stdout and stderr.
+    pub fn new(stdout: bool, stderr: bool) -> FakeTerminal {
+        FakeTerminal { stdout, stderr }
+    }
+
+    /// Both stdout and stderr are terminals.
+    pub fn all() -> FakeTerminal {
+        FakeTerminal::new(true, true)
+    }
+
+    /// Neither stdout nor stderr is a terminal.
+    pub fn none() -> FakeTerminal {
+        FakeTerminal::new(false, false)
+    }
+}
+
+impl TerminalProbe for FakeTerminal {
+    fn is_terminal(&self, stream: Stream) -> bool {
+        match stream {
+            Stream::Stdout => self.stdout,
+            Stream::Stderr => self.stderr,
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use std::io::{self, Write};
+
+use crate::{ColorNope, Stream};
+
+/// A writer which removes ANSI escape sequences when color is disabled.
+///
+/// When [`ColorNope`] allows color for the given [`Stream`], bytes are passed
+/// through untouched. Otherwise CSI (including SGR), OSC and other escape
+/// sequences are removed. Sequences may be split across calls to `write`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::Write;
+/// use color_nope::{ColorNope, Force, Stream, StripWriter};
+///
+/// let color = ColorNope::new(None, None, Some(Force::Off));
+/// let mut writer = StripWriter::new(Vec::new(), &color, Stream::Stdout);
+///
+/// writer.write_all(b"\x1b[1;3").unwrap();
+/// writer.write_all(b"1merror\x1b[0m: ").unwrap();
+/// writer.write_all(b"\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07").unwrap();
+///
+/// assert_eq!(writer.into_inner(), b"error: link");
+/// ```
+///
+/// Use [`keeping_styles`](StripWriter::keeping_styles) to keep attributes
+/// such as bold when only color is disabled.
+#[derive(Debug)]
+pub struct StripWriter<W: Write> {
+    inner: W,
+    mode: Mode,
+    state: State,
+    csi: Vec<u8>,
+    string_len: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mode {
+    PassThrough,
+    KeepStyles,
+    StripAll,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum State {
+    Ground,
+    Escape,
+    EscapeIntermediate,
+    Csi,
+    String,
+}
+
+impl<W: Write> StripWriter<W> {
+    /// Wrap `inner`, stripping escape sequences unless `color` allows color
+    /// for `stream`.
+    pub fn new(inner: W, color: &ColorNope, stream: Stream) -> StripWriter<W> {
+        let mode = if color.enable_color_for(stream) {
+            Mode::PassThrough
+        } else {
+            Mode::StripAll
+        };
+        StripWriter::with_mode(inner, mode)
+    }
+
+    /// Wrap `inner`, like [`new`](StripWriter::new), but keep non-color SGR
+    /// attributes such as bold and underline when `color` allows styles for
+    /// `stream`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Write;
+    /// use color_nope::{ColorNope, FakeTerminal, Stream, StripWriter};
+    ///
+    /// let color = ColorNope::new(Some("xterm".into()), Some("1".into()), None)
+    ///     .with_probe(FakeTerminal::all());
+    /// let mut writer = StripWriter::keeping_styles(Vec::new(), &color, Stream::Stdout);
+    ///
+    /// writer.write_all(b"\x1b[1;31merror\x1b[0m: \x1b[38;5;208mwarning\x1b[39m").unwrap();
+    ///
+    /// assert_eq!(writer.into_inner(), b"\x1b[1merror\x1b[0m: warning");
+    /// ```
+    pub fn keeping_styles(inner: W, color: &ColorNope, stream: Stream) -> StripWriter<W> {
+        let decision = color.decide(stream);
+        let mode = if decision.enabled() {
+            Mode::PassThrough
+        } else if decision.styles_enabled() {
+            Mode::KeepStyles
+        } else {
+            Mode::StripAll
+        };
+        StripWriter::with_mode(inner, mode)
+    }
+
+    fn with_mode(inner: W, mode: Mode) -> StripWriter<W> {
+        StripWriter {
+            inner,
+            mode,
+            state: State::Ground,
+            csi: Vec::new(),
+            string_len: 0,
+        }
+    }
+
+    /// Are escape sequences being removed?
+    pub fn is_stripping(&self) -> bool {
+        self.mode != Mode::PassThrough
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn strip(&mut self, buf: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(buf.len());
+        for &b in buf {
+            self.state = match (self.state, b) {
+                // ESC always starts a new sequence. Inside a string this is
+                // usually the start of ST (`ESC \`), which ends the string.
+                (_, ESC) => State::Escape,
+                (State::Ground, _) => {
+                    out.push(b);
+                    State::Ground
+                }
+                (State::Escape, b'[') => {
+                    self.csi.clear();
+                    State::Csi
+                }
+                // OSC, DCS, SOS, PM and APC are all terminated by BEL or ST.
+                (State::Escape, b']' | b'P' | b'X' | b'^' | b'_') => {
+                    self.string_len = 0;
+                    State::String
+                }
+                // CAN and SUB cancel a sequence.
+                (_, CAN | SUB) => State::Ground,
+                // Other control characters are still carried out in the
+                // middle of a sequence, so are kept.
+                (State::Escape | State::EscapeIntermediate | State::Csi, 0x00..=0x1f) => {
+                    out.push(b);
+                    self.state
+                }
+                (State::Escape | State::EscapeIntermediate, 0x20..=0x2f) => {
+                    State::EscapeIntermediate
+                }
+                (State::Escape | State::EscapeIntermediate, _) => State::Ground,
+                (State::Csi, b'm') if self.mode == Mode::KeepStyles => {
+                    keep_styles(&self.csi, &mut out);
+                    State::Ground
+                }
+                (State::Csi, 0x40..=0x7e) => State::Ground,
+                (State::Csi, _) => {
+                    if self.mode == Mode::KeepStyles && self.csi.len() < MAX_CSI_LEN {
+                        self.csi.push(b);
+                    }
+                    State::Csi
+                }
+                (State::String, BEL) => State::Ground,
+                // Give up on a string which never ends, rather than losing
+                // all remaining output.
+                (State::String, _) if self.string_len >= MAX_STRING_LEN => {
+                    out.push(b);
+                    State::Ground
+                }
+                (State::String, _) => {
+                    self.string_len += 1;
+                    State::String
+                }
+            };
+        }
+        out
+    }
+}
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+const CAN: u8 = 0x18;
+const SUB: u8 = 0x1a;
+
+/// Longer OSC, DCS, SOS, PM and APC strings are treated as unterminated.
+const MAX_STRING_LEN: usize = 64 * 1024;
+
+/// Longer SGR sequences are dropped rather than buffered.
+const MAX_CSI_LEN: usize = 256;
+
+/// Write the SGR sequence with `params`, without any color parameters.
+fn keep_styles(params: &[u8], out: &mut Vec<u8>) {
+    if params.len() >= MAX_CSI_LEN
+        || !params
+            .iter()
+            .all(|b| b.is_ascii_digit() || *b == b';' || *b == b':')
+    {
+        return;
+    }
+    let mut kept: Vec<&[u8]> = Vec::new();
+    let mut params = params.split(|&b| b == b';');
+    while let Some(param) = params.next() {
+        // Only the first sub-parameter (e.g. `38` in `38:5:208`) decides
+        // what the parameter does.
+        let code = param.split(|&b| b == b':').next().unwrap_or_default();
+        match parse_code(code) {
+            // Extended colors, `38;5;n` or `38;2;r;g;b`.
+            Some(38 | 48 | 58) if !param.contains(&b':') => {
+                let skip = match params.next().and_then(parse_code) {
+                    Some(5) => 1,
+                    Some(2) => 3,
+                    _ => 0,
+                };
+                params.by_ref().take(skip).for_each(drop);
+            }
+            Some(30..=39 | 40..=49 | 58 | 59 | 90..=97 | 100..=107) => {}
+            _ => kept.push(param),
+        }
+    }
+    // `ESC[m` has a single empty parameter, so is kept as a reset.
+    if kept.is_empty() {
+        return;
+    }
+    out.extend_from_slice(b"\x1b[");
+    out.extend_from_slice(&kept.join(&b';'));
+    out.push(b'm');
+}
+
+fn parse_code(param: &[u8]) -> Option<u16> {
+    std::str::from_utf8(param).ok()?.parse().ok()
+}
+
+impl<W: Write> Write for StripWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.mode == Mode::PassThrough {
+            return self.inner.write(buf);
+        }
+        let stripped = self.strip(buf);
+        self.inner.write_all(&stripped)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::MAX_STRING_LEN;
+    use crate::{ColorNope, FakeTerminal, Stream, StripWriter};
+
+    fn strip(chunks: &[&[u8]]) -> Vec<u8> {
+        let color = ColorNope::new(Some("xterm".into()), Some("1".into()), None)
+            .with_probe(FakeTerminal::all());
+        let mut writer = StripWriter::new(Vec::new(), &color, Stream::Stdout);
+        for chunk in chunks {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.into_inner()
+    }
+
+    #[test]
+    fn escape_ends_unterminated_string() {
+        assert_eq!(
+            strip(&[b"\x1b]8;;http://x\x1b[31mred\x1b[0m text\nmore lines\n"]),
+            b"red text\nmore lines\n"
+        );
+    }
+
+    #[test]
+    fn string_terminators() {
+        assert_eq!(strip(&[b"a\x1b]0;title\x07b"]), b"ab");
+        assert_eq!(strip(&[b"a\x1b]0;title\x1b", b"\\b"]), b"ab");
+        assert_eq!(strip(&[b"a\x1bPq#0\x1b\\b"]), b"ab");
+    }
+
+    #[test]
+    fn cancelled_sequences() {
+        assert_eq!(strip(&[b"a\x1b[31\x18b"]), b"ab");
+        assert_eq!(strip(&[b"a\x1b]0;title\x1ab"]), b"ab");
+    }
+
+    #[test]
+    fn long_string_is_abandoned() {
+        let mut input = b"\x1b]".to_vec();
+        input.extend(vec![b'x'; MAX_STRING_LEN]);
+        input.extend(b"visible");
+        assert_eq!(strip(&[&input]), b"visible");
+    }
+
+    #[test]
+    fn control_characters_inside_csi() {
+        assert_eq!(strip(&[b"a\x1b[31\nb"]), b"a\n");
+        assert_eq!(strip(&[b"a\x1b[31\n", b"mb"]), b"a\nb");
+        assert_eq!(strip(&[b"a\x1b\r[0mb"]), b"a\rb");
+    }
+}
@@ -0,0 +1,415 @@
+use std::fmt;
+
+use crate::palette::{self, Metric};
+use crate::{ColorLevel, Decision};
+
+/// A terminal color.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Color {
+    #[allow(missing_docs)]
+    Black,
+    #[allow(missing_docs)]
+    Red,
+    #[allow(missing_docs)]
+    Green,
+    #[allow(missing_docs)]
+    Yellow,
+    #[allow(missing_docs)]
+    Blue,
+    #[allow(missing_docs)]
+    Magenta,
+    #[allow(missing_docs)]
+    Cyan,
+    #[allow(missing_docs)]
+    White,
+    #[allow(missing_docs)]
+    BrightBlack,
+    #[allow(missing_docs)]
+    BrightRed,
+    #[allow(missing_docs)]
+    BrightGreen,
+    #[allow(missing_docs)]
+    BrightYellow,
+    #[allow(missing_docs)]
+    BrightBlue,
+    #[allow(missing_docs)]
+    BrightMagenta,
+    #[allow(missing_docs)]
+    BrightCyan,
+    #[allow(missing_docs)]
+    BrightWhite,
+    /// A color from the 256 color xterm palette.
+    Ansi256(u8),
+    /// A 24-bit RGB color.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// The [`ColorLevel`] needed to display this color.
+    pub fn level(&self) -> ColorLevel {
+        match self {
+            Color::Ansi256(_) => ColorLevel::Ansi256,
+            Color::Rgb(..) => ColorLevel::TrueColor,
+            _ => ColorLevel::Basic16,
+        }
+    }
+
+    /// The closest color which can be displayed at `level`, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::palette::Metric;
+    /// use color_nope::{Color, ColorLevel};
+    ///
+    /// let orange = Color::Rgb(255, 135, 0);
+    /// assert_eq!(orange.downsample(ColorLevel::TrueColor, Metric::Rgb), Some(orange));
+    /// assert_eq!(orange.downsample(ColorLevel::Ansi256, Metric::Rgb), Some(Color::Ansi256(208)));
+    /// assert_eq!(orange.downsample(ColorLevel::Basic16, Metric::Rgb), Some(Color::Yellow));
+    /// assert_eq!(orange.downsample(ColorLevel::None, Metric::Rgb), None);
+    /// ```
+    pub fn downsample(self, level: ColorLevel, metric: Metric) -> Option<Color> {
+        if !level.has_color() {
+            return None;
+        }
+        if self.level() <= level {
+            return Some(self);
+        }
+        let (r, g, b) = match self {
+            Color::Rgb(r, g, b) if level == ColorLevel::Ansi256 => {
+                return Some(Color::Ansi256(palette::rgb_to_ansi256(r, g, b, metric)))
+            }
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Ansi256(index) => palette::ansi256_to_rgb(index),
+            basic => return Some(basic),
+        };
+        Some(palette::rgb_to_basic16(r, g, b, metric))
+    }
+
+    /// Write the SGR parameters for this color, where `base` is 30 for the
+    /// foreground and 40 for the background.
+    fn write_sgr(&self, f: &mut fmt::Formatter<'_>, base: u8) -> fmt::Result {
+        use Color::*;
+        let basic = |index: u8| {
+            if index < 8 {
+                base + index
+            } else {
+                base + 60 + index - 8
+            }
+        };
+        let index = match self {
+            Black => 0,
+            Red => 1,
+            Green => 2,
+            Yellow => 3,
+            Blue => 4,
+            Magenta => 5,
+            Cyan => 6,
+            White => 7,
+            BrightBlack => 8,
+            BrightRed => 9,
+            BrightGreen => 10,
+            BrightYellow => 11,
+            BrightBlue => 12,
+            BrightMagenta => 13,
+            BrightCyan => 14,
+            BrightWhite => 15,
+            Ansi256(n) => return write!(f, "{};5;{}", base + 8, n),
+            Rgb(r, g, b) => return write!(f, "{};2;{};{};{}", base + 8, r, g, b),
+        };
+        write!(f, "{}", basic(index))
+    }
+}
+
+/// Text attributes and colors, rendered only when a [`Decision`] allows it.
+///
+/// Colors which the terminal can't display are replaced with the nearest
+/// color it can, based on the [`ColorLevel`] of the decision. Attributes
+/// such as bold are rendered whenever [`Decision::styles_enabled`], even if
+/// color is disabled by `NO_COLOR`.
+///
+/// # Example
+///
+/// ```rust
+/// use color_nope::{Color, ColorNope, FakeTerminal, Force, Stream, Style};
+///
+/// let style = Style::new().fg(Color::Red).bold();
+///
+/// let decision = ColorNope::new(None, None, Some(Force::On)).decide(Stream::Stderr);
+/// assert_eq!(style.paint("error", decision).to_string(), "\x1b[1;31merror\x1b[0m");
+///
+/// let decision = ColorNope::new(None, None, Some(Force::Off)).decide(Stream::Stderr);
+/// assert_eq!(style.paint("error", decision).to_string(), "error");
+///
+/// let decision = ColorNope::new(Some("xterm".into()), Some("1".into()), None)
+///     .with_probe(FakeTerminal::all())
+///     .decide(Stream::Stderr);
+/// assert_eq!(style.paint("error", decision).to_string(), "\x1b[1merror\x1b[0m");
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    metric: Metric,
+}
+
+impl Style {
+    /// A style with no colors or attributes.
+    pub const fn new() -> Style {
+        Style {
+            fg: None,
+            bg: None,
+            bold: false,
+            dim: false,
+            italic: false,
+            underline: false,
+            metric: Metric::Rgb,
+        }
+    }
+
+    /// Set the foreground color.
+    pub fn fg(mut self, color: Color) -> Style {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Set the background color.
+    pub fn bg(mut self, color: Color) -> Style {
+        self.bg = Some(color);
+        self
+    }
+
+    /// Set the [`Metric`] used to pick the nearest color when the terminal
+    /// doesn't support a color directly.
+    pub fn metric(mut self, metric: Metric) -> Style {
+        self.metric = metric;
+        self
+    }
+
+    /// Make the text bold.
+    pub fn bold(mut self) -> Style {
+        self.bold = true;
+        self
+    }
+
+    /// Make the text dim.
+    pub fn dim(mut self) -> Style {
+        self.dim = true;
+        self
+    }
+
+    /// Make the text italic.
+    pub fn italic(mut self) -> Style {
+        self.italic = true;
+        self
+    }
+
+    /// Underline the text.
+    pub fn underline(mut self) -> Style {
+        self.underline = true;
+        self
+    }
+
+    /// Set the foreground color to black.
+    pub fn black(self) -> Style {
+        self.fg(Color::Black)
+    }
+
+    /// Set the foreground color to red.
+    pub fn red(self) -> Style {
+        self.fg(Color::Red)
+    }
+
+    /// Set the foreground color to green.
+    pub fn green(self) -> Style {
+        self.fg(Color::Green)
+    }
+
+    /// Set the foreground color to yellow.
+    pub fn yellow(self) -> Style {
+        self.fg(Color::Yellow)
+    }
+
+    /// Set the foreground color to blue.
+    pub fn blue(self) -> Style {
+        self.fg(Color::Blue)
+    }
+
+    /// Set the foreground color to magenta.
+    pub fn magenta(self) -> Style {
+        self.fg(Color::Magenta)
+    }
+
+    /// Set the foreground color to cyan.
+    pub fn cyan(self) -> Style {
+        self.fg(Color::Cyan)
+    }
+
+    /// Set the foreground color to white.
+    pub fn white(self) -> Style {
+        self.fg(Color::White)
+    }
+
+    /// Apply this style to `value`, rendering it as allowed by `decision`.
+    pub fn paint<T>(self, value: T, decision: Decision) -> Styled<T> {
+        Styled {
+            value,
+            style: self,
+            decision,
+        }
+    }
+
+    /// The escape sequence which starts this style, as allowed by
+    /// `decision`. Empty if nothing would be rendered.
+    pub fn prefix(&self, decision: Decision) -> String {
+        Prefix(self, decision).to_string()
+    }
+
+    /// The escape sequence which resets the terminal after
+    /// [`prefix`](Style::prefix). Empty if the prefix is empty.
+    pub fn reset(&self, decision: Decision) -> &'static str {
+        if self.renders_anything(decision) {
+            "\x1b[0m"
+        } else {
+            ""
+        }
+    }
+
+    fn renders_anything(&self, decision: Decision) -> bool {
+        (decision.styles_enabled() && self.has_attributes())
+            || (decision.level().has_color() && (self.fg.is_some() || self.bg.is_some()))
+    }
+
+    fn has_attributes(&self) -> bool {
+        self.bold || self.dim || self.italic || self.underline
+    }
+}
+
+struct Prefix<'a>(&'a Style, Decision);
+
+impl fmt::Display for Prefix<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Prefix(style, decision) = self;
+        if !style.renders_anything(*decision) {
+            return Ok(());
+        }
+        let level = decision.level();
+
+        f.write_str("\x1b[")?;
+        let mut first = true;
+        let mut sep = |f: &mut fmt::Formatter<'_>| {
+            let result = if first { Ok(()) } else { f.write_str(";") };
+            first = false;
+            result
+        };
+        for (enabled, code) in [
+            (style.bold, "1"),
+            (style.dim, "2"),
+            (style.italic, "3"),
+            (style.underline, "4"),
+        ] {
+            if enabled && decision.styles_enabled() {
+                sep(f)?;
+                f.write_str(code)?;
+            }
+        }
+        if let Some(fg) = style.fg.and_then(|c| c.downsample(level, style.metric)) {
+            sep(f)?;
+            fg.write_sgr(f, 30)?;
+        }
+        if let Some(bg) = style.bg.and_then(|c| c.downsample(level, style.metric)) {
+            sep(f)?;
+            bg.write_sgr(f, 40)?;
+        }
+        f.write_str("m")
+    }
+}
+
+/// A value with a [`Style`], rendered as allowed by a [`Decision`].
+///
+/// Created using [`Decision::style`] or [`Style::paint`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Styled<T> {
+    value: T,
+    style: Style,
+    decision: Decision,
+}
+
+macro_rules! delegate_to_style {
+    ($($name:ident),*) => {
+        $(
+            #[doc = concat!("See [`Style::", stringify!($name), "`].")]
+            pub fn $name(mut self) -> Self {
+                self.style = self.style.$name();
+                self
+            }
+        )*
+    };
+}
+
+impl<T> Styled<T> {
+    /// Set the foreground color.
+    pub fn fg(mut self, color: Color) -> Self {
+        self.style = self.style.fg(color);
+        self
+    }
+
+    /// Set the background color.
+    pub fn bg(mut self, color: Color) -> Self {
+        self.style = self.style.bg(color);
+        self
+    }
+
+    /// See [`Style::metric`].
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.style = self.style.metric(metric);
+        self
+    }
+
+    delegate_to_style! {
+        bold, dim, italic, underline, black, red, green, yellow, blue, magenta, cyan, white
+    }
+
+    /// The [`Style`] which will be applied.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Get the unstyled value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Styled<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            Prefix(&self.style, self.decision),
+            self.value,
+            self.style.reset(self.decision)
+        )
+    }
+}
+
+impl Decision {
+    /// Style `value`, rendering escape sequences only if this decision allows
+    /// color.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::{ColorNope, Force, Stream};
+    ///
+    /// let decision = ColorNope::new(None, None, Some(Force::On)).decide(Stream::Stdout);
+    /// println!("{}: something went wrong", decision.style("error").red().bold());
+    /// ```
+    pub fn style<T>(&self, value: T) -> Styled<T> {
+        Style::new().paint(value, *self)
+    }
+}
@@ -0,0 +1,301 @@
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::{EnvSource, ProcessEnv};
+
+/// The capabilities of a terminal, read from its compiled terminfo entry.
+///
+/// Only the parts relevant to color are kept. Use
+/// [`ColorNope::with_terminfo`](crate::ColorNope::with_terminfo) to take the
+/// `colors` capability into account, so that terminals such as `vt100` and
+/// `xterm-mono` don't get color.
+///
+/// # Example
+///
+/// ```rust
+/// use color_nope::{ColorNope, ProcessEnv, Terminfo};
+///
+/// let terminfo = Terminfo::from_source(&ProcessEnv).ok();
+/// let color = ColorNope::from_env().with_terminfo(terminfo.as_ref());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Terminfo {
+    names: Vec<String>,
+    colors: Option<u32>,
+}
+
+/// Magic number of the legacy format, with 16-bit numbers.
+const MAGIC_LEGACY: u16 = 0o432;
+/// Magic number of the extended format, with 32-bit numbers.
+const MAGIC_32BIT: u16 = 0o1036;
+/// Index of the `colors` capability in the numbers section.
+const COLORS: usize = 13;
+
+impl Terminfo {
+    /// Find and parse the entry for `TERM`, using [`ProcessEnv`].
+    pub fn from_env() -> Result<Terminfo, TerminfoError> {
+        Terminfo::from_source(&ProcessEnv)
+    }
+
+    /// Find and parse the entry for `TERM`, reading variables from `source`.
+    ///
+    /// See [`locate`](Terminfo::locate) for the directories which are
+    /// searched.
+    pub fn from_source(source: &impl EnvSource) -> Result<Terminfo, TerminfoError> {
+        let term = source.var_os("TERM").ok_or(TerminfoError::NotFound)?;
+        let path = Terminfo::locate(&term, source).ok_or(TerminfoError::NotFound)?;
+        let bytes = std::fs::read(path).map_err(TerminfoError::Io)?;
+        Terminfo::parse(&bytes)
+    }
+
+    /// Find the compiled entry for `term`.
+    ///
+    /// Searches the same directories as ncurses, in order:
+    ///
+    /// 1. `$TERMINFO`
+    /// 2. `$HOME/.terminfo`
+    /// 3. Each directory in `$TERMINFO_DIRS`, where an empty entry means
+    ///    `/usr/share/terminfo`
+    /// 4. `/etc/terminfo`, `/lib/terminfo` and `/usr/share/terminfo`
+    ///
+    /// Within each directory, entries are stored in a subdirectory named
+    /// after either the first character of `term` or its hexadecimal value.
+    pub fn locate(term: &OsStr, source: &impl EnvSource) -> Option<PathBuf> {
+        let first = *term.to_str()?.as_bytes().first()?;
+        if term.to_str()?.contains(['/', '\\']) {
+            return None;
+        }
+
+        let mut dirs = Vec::new();
+        if let Some(dir) = source.var_os("TERMINFO") {
+            dirs.push(PathBuf::from(dir));
+        }
+        if let Some(home) = source.var_os("HOME") {
+            dirs.push(Path::new(&home).join(".terminfo"));
+        }
+        if let Some(terminfo_dirs) = source.var_os("TERMINFO_DIRS") {
+            dirs.extend(std::env::split_paths(&terminfo_dirs).map(|dir| {
+                if dir.as_os_str().is_empty() {
+                    PathBuf::from("/usr/share/terminfo")
+                } else {
+                    dir
+                }
+            }));
+        }
+        dirs.extend(
+            ["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo"]
+                .iter()
+                .map(PathBuf::from),
+        );
+
+        let subdirs = [(first as char).to_string(), format!("{first:02x}")];
+        dirs.iter()
+            .flat_map(|dir| subdirs.iter().map(move |sub| dir.join(sub).join(term)))
+            .find(|path| path.is_file())
+    }
+
+    /// Parse a compiled terminfo entry, in either the legacy format or the
+    /// extended format with 32-bit numbers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use color_nope::Terminfo;
+    ///
+    /// // Header: magic, name size, bool count, number count, string count
+    /// // and string table size, followed by the sections themselves.
+    /// let mut entry = vec![0x1a, 0x01, 12, 0, 0, 0, 14, 0, 0, 0, 0, 0];
+    /// entry.extend(b"xterm|xterm\0");
+    /// entry.extend([0xff, 0xff].repeat(13));
+    /// entry.extend(256u16.to_le_bytes());
+    ///
+    /// let terminfo = Terminfo::parse(&entry).unwrap();
+    /// assert_eq!(terminfo.name(), Some("xterm"));
+    /// assert_eq!(terminfo.colors(), Some(256));
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Result<Terminfo, TerminfoError> {
+        let header = |i: usize| -> Result<u16, TerminfoError> {
+            bytes
+                .get(i * 2..i * 2 + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .ok_or(TerminfoError::Invalid("truncated header"))
+        };
+        let number_size = match header(0)? {
+            MAGIC_LEGACY => 2,
+            MAGIC_32BIT => 4,
+            _ => return Err(TerminfoError::Invalid("unknown magic number")),
+        };
+        let names_size = usize::from(header(1)?);
+        let bools_count = usize::from(header(2)?);
+        let numbers_count = usize::from(header(3)?);
+
+        let names_start = 12;
+        let names = bytes
+            .get(names_start..names_start + names_size)
+            .ok_or(TerminfoError::Invalid("truncated names"))?;
+        let names = names
+            .split(|&b| b == 0)
+            .next()
+            .unwrap_or_default()
+            .split(|&b| b == b'|')
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect();
+
+        // The numbers section is aligned to an even byte offset.
+        let mut numbers_start = names_start + names_size + bools_count;
+        numbers_start += numbers_start % 2;
+
+        let colors = if COLORS < numbers_count {
+            let start = numbers_start + COLORS * number_size;
+            let value = bytes
+                .get(start..start + number_size)
+                .ok_or(TerminfoError::Invalid("truncated numbers"))?;
+            let value = match *value {
+                [a, b] => i32::from(i16::from_le_bytes([a, b])),
+                [a, b, c, d] => i32::from_le_bytes([a, b, c, d]),
+                _ => unreachable!("number size is 2 or 4"),
+            };
+            // Negative values mean the capability is absent or cancelled.
+            u32::try_from(value).ok()
+        } else {
+            None
+        };
+
+        Ok(Terminfo { names, colors })
+    }
+
+    /// The primary name of the terminal.
+    pub fn name(&self) -> Option<&str> {
+        self.names.first().map(String::as_str)
+    }
+
+    /// All names of the terminal, including aliases and the description.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The number of colors the terminal supports, if any.
+    pub fn colors(&self) -> Option<u32> {
+        self.colors
+    }
+}
+
+/// An error returned when reading a [`Terminfo`] entry.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TerminfoError {
+    /// `TERM` is not set, or no entry was found for it.
+    NotFound,
+    /// The entry could not be read.
+    Io(std::io::Error),
+    /// The entry is not a valid compiled terminfo entry.
+    Invalid(&'static str),
+}
+
+impl fmt::Display for TerminfoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TerminfoError::NotFound => f.write_str("no terminfo entry found"),
+            TerminfoError::Io(err) => write!(f, "failed to read terminfo entry: {err}"),
+            TerminfoError::Invalid(reason) => write!(f, "invalid terminfo entry: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for TerminfoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TerminfoError::Io(err) => Some(err),
+            TerminfoError::NotFound | TerminfoError::Invalid(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Terminfo, TerminfoError, COLORS, MAGIC_32BIT, MAGIC_LEGACY};
+    use crate::{ColorLevel, ColorNope, FakeTerminal, Stream};
+
+    /// Build an entry named `name`, with `numbers` in the numbers section.
+    fn entry(magic: u16, name: &str, numbers: &[i32]) -> Vec<u8> {
+        let names = format!("{name}\0");
+        let mut bytes = Vec::new();
+        for value in [magic, names.len() as u16, 0, numbers.len() as u16, 0, 0] {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.extend(names.as_bytes());
+        if bytes.len() % 2 == 1 {
+            bytes.push(0);
+        }
+        for &number in numbers {
+            if magic == MAGIC_32BIT {
+                bytes.extend(number.to_le_bytes());
+            } else {
+                bytes.extend((number as i16).to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    fn numbers_with_colors(colors: i32) -> Vec<i32> {
+        let mut numbers = vec![-1; COLORS];
+        numbers.push(colors);
+        numbers
+    }
+
+    #[test]
+    fn extended_format_with_24_bit_colors() {
+        let bytes = entry(MAGIC_32BIT, "xterm-direct", &numbers_with_colors(1 << 24));
+        let terminfo = Terminfo::parse(&bytes).unwrap();
+        assert_eq!(terminfo.name(), Some("xterm-direct"));
+        assert_eq!(terminfo.colors(), Some(16_777_216));
+    }
+
+    #[test]
+    fn legacy_format() {
+        let bytes = entry(MAGIC_LEGACY, "xterm-256color", &numbers_with_colors(256));
+        assert_eq!(Terminfo::parse(&bytes).unwrap().colors(), Some(256));
+    }
+
+    #[test]
+    fn absent_colors() {
+        let bytes = entry(MAGIC_LEGACY, "vt100", &numbers_with_colors(-1));
+        assert_eq!(Terminfo::parse(&bytes).unwrap().colors(), None);
+    }
+
+    #[test]
+    fn numbers_section_without_colors() {
+        for magic in [MAGIC_LEGACY, MAGIC_32BIT] {
+            for count in [0, 5, COLORS] {
+                let bytes = entry(magic, "vt100", &vec![80; count]);
+                assert_eq!(Terminfo::parse(&bytes).unwrap().colors(), None);
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_numbers() {
+        for magic in [MAGIC_LEGACY, MAGIC_32BIT] {
+            let mut bytes = entry(magic, "xterm", &numbers_with_colors(256));
+            bytes.pop();
+            assert!(matches!(
+                Terminfo::parse(&bytes),
+                Err(TerminfoError::Invalid("truncated numbers"))
+            ));
+        }
+    }
+
+    #[test]
+    fn with_terminfo_raises_level_to_true_color() {
+        let bytes = entry(MAGIC_32BIT, "xterm-direct", &numbers_with_colors(1 << 24));
+        let terminfo = Terminfo::parse(&bytes).unwrap();
+
+        let color =
+            ColorNope::new(Some("xterm".into()), None, None).with_probe(FakeTerminal::all());
+        assert_eq!(color.color_level_for(Stream::Stdout), ColorLevel::Basic16);
+
+        let color = color.with_terminfo(Some(&terminfo));
+        assert_eq!(color.color_level_for(Stream::Stdout), ColorLevel::TrueColor);
+    }
+}
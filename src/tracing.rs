@@ -0,0 +1,111 @@
+//! Integration with [`tracing_subscriber`]'s `fmt` layer.
+//!
+//! Each helper enables ANSI output based on the stream or handle the layer
+//! actually writes to. Requires the `tracing` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use color_nope::{ColorNope, Stream};
+//! use tracing_subscriber::prelude::*;
+//!
+//! let color = ColorNope::from_env();
+//! let subscriber =
+//!     tracing_subscriber::registry().with(color_nope::tracing::layer(&color, Stream::Stderr));
+//! ```
+
+use std::io::{self, IsTerminal};
+use std::sync::Arc;
+
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::{DefaultFields, Format};
+use tracing_subscriber::fmt::writer::{EitherWriter, MakeWriter};
+use tracing_subscriber::fmt::{self, Layer, SubscriberBuilder};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::{ColorNope, Stream};
+
+/// Create a `fmt` layer which writes to `stream`, with ANSI output enabled
+/// if `color` allows color for it.
+pub fn layer<S>(color: &ColorNope, stream: Stream) -> Layer<S, DefaultFields, Format, StreamWriter>
+where
+    S: tracing_core::Subscriber + for<'a> LookupSpan<'a>,
+{
+    fmt::layer()
+        .with_writer(StreamWriter::new(stream))
+        .with_ansi(color.enable_color_for(stream))
+}
+
+/// Create a `fmt` layer which writes to `handle`, such as a [`File`], with
+/// ANSI output enabled if `color` allows color for it.
+///
+/// [`File`]: std::fs::File
+///
+/// # Example
+///
+/// ```rust
+/// use color_nope::ColorNope;
+/// use tracing_subscriber::prelude::*;
+///
+/// # let path = std::env::temp_dir().join("color-nope-tracing-doctest.log");
+/// let file = std::fs::File::create(path).unwrap();
+/// let layer = color_nope::tracing::layer_for_handle(&ColorNope::from_env(), file);
+/// let subscriber = tracing_subscriber::registry().with(layer);
+/// ```
+pub fn layer_for_handle<S, H>(
+    color: &ColorNope,
+    handle: H,
+) -> Layer<S, DefaultFields, Format, Arc<H>>
+where
+    S: tracing_core::Subscriber + for<'a> LookupSpan<'a>,
+    H: IsTerminal + Send + Sync + 'static,
+    for<'a> &'a H: io::Write,
+{
+    let ansi = color.enable_color_for_handle(&handle);
+    fmt::layer().with_writer(Arc::new(handle)).with_ansi(ansi)
+}
+
+/// Create a subscriber builder, like [`tracing_subscriber::fmt()`], which
+/// writes to `stream` with ANSI output enabled if `color` allows color for it.
+///
+/// # Example
+///
+/// ```rust
+/// use color_nope::{ColorNope, Stream};
+///
+/// color_nope::tracing::builder(&ColorNope::from_env(), Stream::Stderr)
+///     .with_max_level(tracing_subscriber::filter::LevelFilter::DEBUG)
+///     .init();
+/// ```
+pub fn builder(
+    color: &ColorNope,
+    stream: Stream,
+) -> SubscriberBuilder<DefaultFields, Format, LevelFilter, StreamWriter> {
+    fmt::fmt()
+        .with_writer(StreamWriter::new(stream))
+        .with_ansi(color.enable_color_for(stream))
+}
+
+/// A [`MakeWriter`] for either stdout or stderr, chosen at runtime.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StreamWriter {
+    stream: Stream,
+}
+
+impl StreamWriter {
+    /// Write to `stream`.
+    pub fn new(stream: Stream) -> StreamWriter {
+        StreamWriter { stream }
+    }
+}
+
+impl<'a> MakeWriter<'a> for StreamWriter {
+    type Writer = EitherWriter<io::Stdout, io::Stderr>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        match self.stream {
+            Stream::Stdout => EitherWriter::A(io::stdout()),
+            Stream::Stderr => EitherWriter::B(io::stderr()),
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::process::{Command, Output};
+
+/// Run the `color-nope` binary with only the given environmental variables.
+fn run(env: &[(&str, &str)], args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_color-nope"))
+        .args(args)
+        .env_clear()
+        .envs(env.iter().copied())
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+fn stderr(output: &Output) -> &str {
+    std::str::from_utf8(&output.stderr).unwrap()
+}
+
+#[test]
+fn no_color() {
+    let env = [("TERM", "xterm"), ("NO_COLOR", "1")];
+
+    assert_eq!(run(&env, &["check"]).status.code(), Some(1));
+    assert_eq!(run(&env, &["check", "stderr"]).status.code(), Some(1));
+
+    let output = run(&env, &["level"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "0\n");
+
+    let output = run(&env, &["explain"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "stdout: color disabled: NO_COLOR is set\nstderr: color disabled: NO_COLOR is set\n"
+    );
+}
+
+#[test]
+fn force_color() {
+    let env = [("TERM", "xterm"), ("FORCE_COLOR", "2")];
+
+    assert_eq!(run(&env, &["check"]).status.code(), Some(0));
+
+    let output = run(&env, &["level", "stderr"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "256\n");
+
+    let output = run(&env, &["explain", "stdout"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "color enabled (256 colors): FORCE_COLOR requests 256 colors\n"
+    );
+}
+
+#[test]
+fn not_a_terminal() {
+    let output = run(&[("TERM", "xterm")], &["explain", "stdout"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "color disabled: the output is not a terminal\n"
+    );
+}
+
+#[test]
+fn invalid_force_color() {
+    let output = run(&[("FORCE_COLOR", "yes")], &["check"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "color-nope: invalid value \"yes\" for FORCE_COLOR, expected 0, 1, 2, 3, true or false\n"
+    );
+}
+
+#[test]
+fn unknown_stream() {
+    let output = run(&[], &["check", "stdin"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("color-nope: unknown stream `stdin`\n"));
+}
+
+#[test]
+fn unknown_command() {
+    let output = run(&[], &["paint"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("color-nope: unknown command `paint`\n"));
+}
+
+#[test]
+fn missing_command() {
+    let output = run(&[], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("color-nope: expected a command\n"));
+}
+
+#[test]
+fn help_and_version() {
+    let output = run(&[], &["--help"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("Usage: color-nope <COMMAND> [STREAM]\n"));
+
+    let output = run(&[], &["-V"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("color-nope {}\n", env!("CARGO_PKG_VERSION"))
+    );
+}
+
+/// Run the binary with stderr connected to a pseudo-terminal, and stdout
+/// captured as it would be by `$(...)`.
+#[cfg(unix)]
+fn run_with_tty_stderr(env: &[(&str, &str)], args: &[&str]) -> Output {
+    use std::fs::File;
+    use std::os::unix::io::FromRawFd;
+    use std::process::Stdio;
+
+    let (mut leader, mut follower) = (0, 0);
+    // SAFETY: both pointers are valid, and the optional arguments are null.
+    let result = unsafe {
+        libc::openpty(
+            &mut leader,
+            &mut follower,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    assert_eq!(result, 0, "openpty failed");
+    // SAFETY: openpty returned two new file descriptors, owned from here on.
+    let (_leader, follower) = unsafe { (File::from_raw_fd(leader), File::from_raw_fd(follower)) };
+
+    Command::new(env!("CARGO_BIN_EXE_color-nope"))
+        .args(args)
+        .env_clear()
+        .envs(env.iter().copied())
+        .stderr(Stdio::from(follower))
+        .output()
+        .unwrap()
+}
+
+#[cfg(unix)]
+#[test]
+fn level_defaults_to_stderr_when_captured() {
+    let env = [("TERM", "xterm-256color")];
+
+    let output = run_with_tty_stderr(&env, &["level"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "256\n");
+
+    let output = run_with_tty_stderr(&env, &["level", "stdout"]);
+    assert_eq!(stdout(&output), "0\n");
+}